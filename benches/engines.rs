@@ -0,0 +1,71 @@
+use anyhow::Result;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use tokio::runtime::Runtime;
+
+use behavior_tree::{BT, Builder, Engines, Executor};
+
+// Action that finishes immediately, so the benchmark measures the engine instead of the executors
+struct Instant {
+    succeed: bool,
+}
+
+impl Executor for Instant {
+    fn get_name(&self) -> String {
+        "instant".to_string()
+    }
+
+    async fn execute(&mut self) -> Result<bool> {
+        Ok(self.succeed)
+    }
+}
+
+// Builds a tree of alternating sequences and fallbacks, the fallbacks only succeed on their last
+// child so most of the tree is visited during a run
+fn generate_tree(width: usize, depth: usize, succeed: bool) -> BT<Builder> {
+    if depth == 0 {
+        return BT::action(Instant { succeed });
+    }
+
+    if depth % 2 == 0 {
+        let children = (0..width)
+            .map(|_| generate_tree(width, depth - 1, succeed))
+            .collect();
+        BT::seq(children)
+    } else {
+        let children = (0..width)
+            .map(|i| generate_tree(width, depth - 1, succeed && i == width - 1))
+            .collect();
+        BT::fb(children)
+    }
+}
+
+fn node_count(width: usize, depth: usize) -> usize {
+    (0..=depth).map(|d| width.pow(d as u32)).sum()
+}
+
+fn bench_engines(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("run_generated_tree");
+    group.sample_size(10);
+
+    for (width, depth) in [(4, 3), (4, 4), (7, 4)] {
+        for engine in [Engines::Static, Engines::Dynamic] {
+            let id = BenchmarkId::new(format!("{engine:?}"), node_count(width, depth));
+            group.bench_with_input(id, &(width, depth), |b, &(width, depth)| {
+                b.to_async(&rt).iter(|| async move {
+                    BT::new()
+                        .set_engine(engine)
+                        .root(generate_tree(width, depth, true))
+                        .run()
+                        .await
+                        .result()
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_engines);
+criterion_main!(benches);
@@ -0,0 +1,111 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Ident, LitStr, Type};
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Kind {
+    Executor,
+    Evaluator,
+}
+
+// #[bt(name = "dock", execute = "run", halt = "stop")] on an executor,
+// #[bt(name = "battery_ok", evaluate = "check", value = "i32")] on an evaluator.
+// The methods take &mut self, so the struct keeps its state across executions. They may be async or not,
+// and return a bool or a Result<bool, E>
+#[derive(Default)]
+struct Options {
+    name: Option<LitStr>,
+    run: Option<Ident>,
+    halt: Option<Ident>,
+    value: Option<Type>,
+}
+
+impl Options {
+    fn parse(kind: Kind, input: &DeriveInput) -> syn::Result<Options> {
+        let mut options = Options::default();
+        let run_key = match kind {
+            Kind::Executor => "execute",
+            Kind::Evaluator => "evaluate",
+        };
+        for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("bt")) {
+            attr.parse_nested_meta(|meta| {
+                let key = meta.path.get_ident().map(|ident| ident.to_string()).unwrap_or_default();
+                match key.as_str() {
+                    "name" => options.name = Some(meta.value()?.parse()?),
+                    key if key == run_key => options.run = Some(meta.value()?.parse::<LitStr>()?.parse()?),
+                    "halt" if kind == Kind::Executor => options.halt = Some(meta.value()?.parse::<LitStr>()?.parse()?),
+                    "value" if kind == Kind::Evaluator => options.value = Some(meta.value()?.parse::<LitStr>()?.parse()?),
+                    _ => {
+                        let expected = match kind {
+                            Kind::Executor => "`name`, `execute` or `halt`",
+                            Kind::Evaluator => "`name`, `evaluate` or `value`",
+                        };
+                        return Err(meta.error(format!("unsupported bt property, expected {expected}")));
+                    }
+                }
+                Ok(())
+            })?;
+        }
+
+        if options.run.is_none() {
+            return Err(syn::Error::new(input.ident.span(), format!("add #[bt({run_key} = \"method\")] with the method to call")));
+        }
+        if kind == Kind::Evaluator && options.value.is_none() {
+            return Err(syn::Error::new(input.ident.span(), "add #[bt(value = \"Type\")] with the type of the watched value"));
+        }
+        Ok(options)
+    }
+}
+
+pub(crate) fn expand(kind: Kind, input: DeriveInput) -> syn::Result<TokenStream> {
+    let options = Options::parse(kind, &input)?;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // The name defaults to the name of the struct
+    let name = options.name.unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()));
+    let run = options.run.expect("checked when parsed");
+
+    let expanded = match kind {
+        Kind::Executor => {
+            let halt = options.halt.map(|halt| quote! {
+                fn halt(&mut self) {
+                    self.#halt()
+                }
+            });
+            quote! {
+                impl #impl_generics ::behavior_tree::Executor for #ident #ty_generics #where_clause {
+                    fn get_name(&self) -> ::std::string::String {
+                        #name.to_string()
+                    }
+
+                    async fn execute(&mut self) -> ::core::result::Result<bool, ::behavior_tree::anyhow::Error> {
+                        use ::behavior_tree::{FutureKind as _, ValueKind as _};
+                        let output = self.#run();
+                        (&output).output_kind().resolve(output).await
+                    }
+
+                    #halt
+                }
+            }
+        }
+        Kind::Evaluator => {
+            let value = options.value.expect("checked when parsed");
+            quote! {
+                impl #impl_generics ::behavior_tree::Evaluator<#value> for #ident #ty_generics #where_clause {
+                    fn get_name(&self) -> ::std::string::String {
+                        #name.to_string()
+                    }
+
+                    async fn evaluate(&mut self, val: #value) -> ::core::result::Result<bool, ::behavior_tree::anyhow::Error> {
+                        use ::behavior_tree::{FutureKind as _, ValueKind as _};
+                        let output = self.#run(val);
+                        (&output).output_kind().resolve(output).await
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(expanded)
+}
@@ -0,0 +1,244 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{braced, parenthesized, parse::{Parse, ParseStream}, punctuated::Punctuated, token, Expr, Ident, LitStr, Token};
+
+// bt! { seq { cond(handle, BatteryOk::new()), fb { action(Dock::new()), action(Alarm::new()) } } }
+// Nodes that pick a child take it after an arrow: weighted { 2.0 => node, .. },
+// utility(name, handle[, margin]) { |value| score => node, .. } and switch(name, handle) { value => node, .., _ => node }.
+// A leading name, as in bt! { "robot": seq { .. } }, names the tree and gives a tree ready to run.
+// Any node can be followed by .name(..), .description(..) and .meta(key, value) like in the builder
+pub(crate) struct TreeDef {
+    name: Option<LitStr>,
+    root: NodeDef,
+}
+
+impl Parse for TreeDef {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = if input.peek(LitStr) {
+            let name = input.parse()?;
+            input.parse::<Token![:]>()?;
+            Some(name)
+        } else {
+            None
+        };
+        let root = input.parse()?;
+        if !input.is_empty() {
+            return Err(input.error("a tree has a single root, wrap the nodes in a composite like `seq { .. }`"));
+        }
+        Ok(Self { name, root })
+    }
+}
+
+impl TreeDef {
+    pub(crate) fn expand(&self) -> TokenStream {
+        let root = &self.root.0;
+        match &self.name {
+            Some(name) => quote! { ::behavior_tree::BT::new().name(#name).root(#root) },
+            None => root.clone(),
+        }
+    }
+}
+
+// A node expanded to the builder call that creates it
+struct NodeDef(TokenStream);
+
+const LABELS: [(&str, usize); 3] = [("name", 1), ("description", 1), ("meta", 2)];
+
+const COMPOSITES: [&str; 6] = ["seq", "fb", "seq_with_memory", "fb_with_memory", "random_seq", "random_fb"];
+const DECORATORS: [(&str, usize); 6] = [
+    ("run_once", 0),
+    ("checked_once", 0),
+    ("cooldown", 1),
+    ("rate_limit", 2),
+    ("delay", 1),
+    ("keep_running_until_failure", 0),
+];
+const LEAVES: [&str; 8] = ["action", "action_blocking", "action_fn", "cond", "condition", "success", "failure", "tree"];
+
+impl Parse for NodeDef {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let NodeDef(mut node) = NodeDef::parse_node(input)?;
+        while input.peek(Token![.]) {
+            input.parse::<Token![.]>()?;
+            let label: Ident = input.parse()?;
+            let Some((_, arity)) = LABELS.iter().find(|(name, _)| label == name) else {
+                return Err(syn::Error::new(label.span(), format!("unknown label `{label}`, expected name, description or meta")));
+            };
+            let content;
+            parenthesized!(content in input);
+            let args = content.parse_terminated(Expr::parse, Token![,])?.into_iter().collect::<Vec<_>>();
+            arg_count(&label, &args, &[*arity])?;
+            node = quote! { #node.#label( #( #args ),* ) };
+        }
+        Ok(NodeDef(node))
+    }
+}
+
+impl NodeDef {
+    fn parse_node(input: ParseStream) -> syn::Result<Self> {
+        let kind: Ident = input.parse()?;
+        let name = kind.to_string();
+        let span = kind.span();
+
+        let args = if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            Some(content.parse_terminated(Expr::parse, Token![,])?)
+        } else {
+            None
+        };
+        let args = args.unwrap_or_default().into_iter().collect::<Vec<_>>();
+
+        if COMPOSITES.contains(&name.as_str()) {
+            no_args(&kind, &args)?;
+            let children = children(input, &kind)?.into_iter().map(|child| child.0);
+            let builder = Ident::new(&name, span);
+            return Ok(Self(quote_spanned! {span=> ::behavior_tree::BT::#builder(vec![ #( #children ),* ]) }));
+        }
+
+        if name == "weighted" {
+            no_args(&kind, &args)?;
+            let children = arms(input, &kind, "weight")?.into_iter().map(|ArmDef(weight, child)| {
+                let child = child.0;
+                quote! { (#weight, #child) }
+            });
+            return Ok(Self(quote_spanned! {span=> ::behavior_tree::BT::weighted(vec![ #( #children ),* ]) }));
+        }
+
+        if name == "utility" {
+            arg_count(&kind, &args, &[2, 3])?;
+            let (selector, margin) = (&args[..2], args.get(2).map(|margin| quote! { .margin(#margin) }));
+            let children = arms(input, &kind, "score")?.into_iter().map(|ArmDef(score, child)| {
+                let child = child.0;
+                quote! { .child(#score, #child) }
+            });
+            return Ok(Self(quote_spanned! {span=>
+                ::behavior_tree::BT::utility(::behavior_tree::UtilitySelector::new( #( #selector ),* ) #margin #( #children )*)
+            }));
+        }
+
+        if name == "switch" {
+            arg_count(&kind, &args, &[2])?;
+            let arms = arms(input, &kind, "value")?;
+            let defaults = arms.iter().filter(|ArmDef(value, _)| matches!(value, Expr::Infer(_))).count();
+            if defaults > 1 {
+                return Err(syn::Error::new(span, format!("`switch` takes a single default `_ => node`, found {defaults}")));
+            }
+            let arms = arms.into_iter().map(|ArmDef(value, child)| {
+                let child = child.0;
+                match value {
+                    Expr::Infer(_) => quote! { .default(#child) },
+                    value => quote! { .case(#value, #child) },
+                }
+            });
+            return Ok(Self(quote_spanned! {span=>
+                ::behavior_tree::BT::switch(::behavior_tree::Switch::new( #( #args ),* ) #( #arms )*)
+            }));
+        }
+
+        if let Some((_, arity)) = DECORATORS.iter().find(|(decorator, _)| *decorator == name) {
+            arg_count(&kind, &args, &[*arity])?;
+            let mut children = children(input, &kind)?;
+            if children.len() != 1 {
+                return Err(syn::Error::new(span, format!("`{name}` decorates a single child, found {}", children.len())));
+            }
+            let child = children.remove(0).0;
+            let builder = Ident::new(&name, span);
+            return Ok(Self(quote_spanned! {span=> ::behavior_tree::BT::#builder( #( #args, )* #child ) }));
+        }
+
+        if LEAVES.contains(&name.as_str()) {
+            if input.peek(token::Brace) {
+                return Err(syn::Error::new(span, format!("`{name}` is a leaf and has no children")));
+            }
+            let expanded = match name.as_str() {
+                "action" | "action_blocking" => {
+                    arg_count(&kind, &args, &[1])?;
+                    let builder = Ident::new(&name, span);
+                    quote_spanned! {span=> ::behavior_tree::BT::#builder( #( #args ),* ) }
+                }
+                "success" | "failure" => {
+                    no_args(&kind, &args)?;
+                    let builder = Ident::new(&name, span);
+                    quote_spanned! {span=> ::behavior_tree::BT::#builder() }
+                }
+                "action_fn" => {
+                    arg_count(&kind, &args, &[2])?;
+                    quote_spanned! {span=> ::behavior_tree::BT::action_fn( #( #args ),* ) }
+                }
+                "cond" | "condition" => {
+                    arg_count(&kind, &args, &[2, 3])?;
+                    match args.len() {
+                        2 => quote_spanned! {span=> ::behavior_tree::BT::condition( #( #args ),* ) },
+                        _ => quote_spanned! {span=> ::behavior_tree::BT::condition_with( #( #args ),* ) },
+                    }
+                }
+                _ => {
+                    // An existing subtree, e.g. one built by a function
+                    arg_count(&kind, &args, &[1])?;
+                    let tree = &args[0];
+                    quote! { #tree }
+                }
+            };
+            return Ok(Self(expanded));
+        }
+
+        Err(syn::Error::new(span, format!(
+            "unknown node `{name}`, expected one of {}, weighted, utility, switch, {} or {}",
+            COMPOSITES.join(", "),
+            DECORATORS.iter().map(|(decorator, _)| *decorator).collect::<Vec<_>>().join(", "),
+            LEAVES.join(", "),
+        )))
+    }
+}
+
+// A child after the value that picks it, `value => node`
+struct ArmDef(Expr, NodeDef);
+
+impl Parse for ArmDef {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let weight = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        Ok(Self(weight, input.parse()?))
+    }
+}
+
+// The children of a composite or decorator, between braces and separated by commas
+fn children(input: ParseStream, kind: &Ident) -> syn::Result<Vec<NodeDef>> {
+    if !input.peek(token::Brace) {
+        return Err(syn::Error::new(kind.span(), format!("`{kind}` expects its children as `{{ .. }}`")));
+    }
+    let content;
+    braced!(content in input);
+    let children: Punctuated<NodeDef, Token![,]> = content.parse_terminated(NodeDef::parse, Token![,])?;
+    if children.is_empty() {
+        return Err(syn::Error::new(kind.span(), format!("`{kind}` needs at least one child")));
+    }
+    Ok(children.into_iter().collect())
+}
+
+// The children of a node that picks one, between braces as `{ value => node, .. }`
+fn arms(input: ParseStream, kind: &Ident, value: &str) -> syn::Result<Vec<ArmDef>> {
+    if !input.peek(token::Brace) {
+        return Err(syn::Error::new(kind.span(), format!("`{kind}` expects its children as `{{ {value} => node, .. }}`")));
+    }
+    let content;
+    braced!(content in input);
+    let arms: Punctuated<ArmDef, Token![,]> = content.parse_terminated(ArmDef::parse, Token![,])?;
+    if arms.is_empty() {
+        return Err(syn::Error::new(kind.span(), format!("`{kind}` needs at least one child")));
+    }
+    Ok(arms.into_iter().collect())
+}
+
+fn no_args(kind: &Ident, args: &[Expr]) -> syn::Result<()> {
+    arg_count(kind, args, &[0])
+}
+
+fn arg_count(kind: &Ident, args: &[Expr], expected: &[usize]) -> syn::Result<()> {
+    if expected.contains(&args.len()) {
+        return Ok(());
+    }
+    let expected = expected.iter().map(|count| count.to_string()).collect::<Vec<_>>().join(" or ");
+    Err(syn::Error::new(kind.span(), format!("`{kind}` takes {expected} arguments, found {}", args.len())))
+}
@@ -0,0 +1,329 @@
+use convert_case::{Case, Casing};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, Parser}, parse_quote, spanned::Spanned, Attribute, FnArg, GenericParam, Generics, Ident, ImplItem,
+    Item, ItemImpl, LitStr, Pat, ReturnType, Signature, Token, Type, meta::ParseNestedMeta,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Kind {
+    Action,
+    Condition,
+}
+
+impl Kind {
+    fn attr(self) -> &'static str {
+        match self {
+            Kind::Action => "bt_action",
+            Kind::Condition => "bt_condition",
+        }
+    }
+
+    fn from_attr(attr: &Attribute) -> Option<Kind> {
+        [Kind::Action, Kind::Condition].into_iter().find(|kind| attr.path().is_ident(kind.attr()))
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            Kind::Action => "Executor",
+            Kind::Condition => "Evaluator",
+        }
+    }
+}
+
+// On a function it generates the executor or evaluator next to it. On an impl block it does so
+// for every method in there marked with #[bt_action] or #[bt_condition], either attribute on the block expands both
+pub(crate) fn expand(kind: Kind, attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    match syn::parse2::<Item>(item)? {
+        Item::Fn(item_fn) => {
+            let mut options = Options::default();
+            Parser::parse2(syn::meta::parser(|meta| options.parse(kind, meta)), attr)?;
+            let generated = Leaf::new(kind, &item_fn.sig, None, options)?.generate();
+            Ok(quote! { #item_fn #generated })
+        }
+        Item::Impl(mut item_impl) => {
+            if !attr.is_empty() {
+                return Err(syn::Error::new(attr.span(), format!("put the options of `{}` on the methods", kind.attr())));
+            }
+            // The methods are expanded already, another attribute on the block has nothing left to do
+            item_impl.attrs.retain(|attr| Kind::from_attr(attr).is_none());
+            let self_impl = item_impl.clone();
+            let mut generated = vec![];
+            for impl_item in item_impl.items.iter_mut() {
+                let ImplItem::Fn(method) = impl_item else { continue };
+                let Some(pos) = method.attrs.iter().position(|attr| Kind::from_attr(attr).is_some()) else { continue };
+                let attr = method.attrs.remove(pos);
+                let method_kind = Kind::from_attr(&attr).expect("found by kind");
+                let options = Options::from_attr(method_kind, &attr)?;
+                generated.push(Leaf::new(method_kind, &method.sig, Some(&self_impl), options)?.generate());
+            }
+            if generated.is_empty() {
+                return Err(syn::Error::new(item_impl.impl_token.span, "no method in here is marked with `#[bt_action]` or `#[bt_condition]`"));
+            }
+            Ok(quote! { #item_impl #( #generated )* })
+        }
+        item => Err(syn::Error::new(item.span(), format!("`{}` expects a function or an impl block", kind.attr()))),
+    }
+}
+
+#[derive(Default)]
+struct Options {
+    watch: Option<Vec<Ident>>,
+    name: Option<LitStr>,
+}
+
+impl Options {
+    fn from_attr(kind: Kind, attr: &Attribute) -> syn::Result<Options> {
+        let mut options = Options::default();
+        if let syn::Meta::List(_) = attr.meta {
+            attr.parse_nested_meta(|meta| options.parse(kind, meta))?;
+        }
+        Ok(options)
+    }
+
+    // #[bt_condition(watch = [a, b])] watches the arguments a and b, by default only the first argument is watched.
+    // name = "..." replaces the function name as name of the node
+    fn parse(&mut self, kind: Kind, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if kind == Kind::Condition && meta.path.is_ident("watch") {
+            let value = meta.value()?;
+            let content;
+            syn::bracketed!(content in value);
+            self.watch = Some(content.parse_terminated(Ident::parse, Token![,])?.into_iter().collect());
+            Ok(())
+        } else if kind == Kind::Condition {
+            Err(meta.error("unsupported bt_condition property, expected `watch = [..]` or `name = \"..\"`"))
+        } else {
+            Err(meta.error("unsupported bt_action property, expected `name = \"..\"`"))
+        }
+    }
+}
+
+enum Receiver {
+    None,
+    Ref,
+    Value,
+}
+
+struct Arg {
+    name: Option<Ident>, // Watched arguments may use a pattern like _
+    ty: Type,
+}
+
+struct Leaf<'a> {
+    kind: Kind,
+    sig: &'a Signature,
+    self_ty: Option<&'a Type>,
+    receiver: Receiver,
+    args: Vec<Arg>,
+    watched: Vec<usize>,
+    generics: Generics,
+    name: Option<LitStr>,
+}
+
+impl<'a> Leaf<'a> {
+    fn new(kind: Kind, sig: &'a Signature, item_impl: Option<&'a ItemImpl>, options: Options) -> syn::Result<Leaf<'a>> {
+        let fn_name = &sig.ident;
+
+        // The generated struct owns its arguments, so it can't borrow
+        let impl_generics = item_impl.map(|item_impl| &item_impl.generics);
+        for param in impl_generics.into_iter().chain([&sig.generics]).flat_map(|generics| generics.params.iter()) {
+            if let GenericParam::Lifetime(lifetime) = param {
+                return Err(syn::Error::new(lifetime.span(), format!("`{fn_name}` can't have lifetime parameters, its arguments are stored")));
+            }
+        }
+
+        let mut receiver = Receiver::None;
+        let mut args = vec![];
+        for input in &sig.inputs {
+            match input {
+                FnArg::Receiver(recv) if item_impl.is_none() => {
+                    return Err(syn::Error::new(recv.span(), format!("mark the impl block of `{fn_name}` with `#[{}]` as well", kind.attr())));
+                }
+                FnArg::Receiver(recv) if recv.colon_token.is_some() => {
+                    return Err(syn::Error::new(recv.span(), "use `self`, `&self` or `&mut self` as receiver"));
+                }
+                FnArg::Receiver(recv) => receiver = if recv.reference.is_some() { Receiver::Ref } else { Receiver::Value },
+                FnArg::Typed(pat) => {
+                    if let Type::ImplTrait(ty) = &*pat.ty {
+                        return Err(syn::Error::new(ty.span(), "use a generic parameter instead of `impl Trait`"));
+                    }
+                    let name = match &*pat.pat {
+                        Pat::Ident(p) if p.by_ref.is_none() && p.subpat.is_none() => Some(p.ident.clone()),
+                        _ => None,
+                    };
+                    args.push(Arg { name, ty: (*pat.ty).clone() });
+                }
+            }
+        }
+
+        // Positions of the watched arguments, a condition needs at least one
+        let watched = match (kind, options.watch) {
+            (Kind::Action, _) => vec![],
+            (Kind::Condition, None) if args.is_empty() => {
+                return Err(syn::Error::new(sig.span(), format!("`{fn_name}` needs an argument with the watched value")));
+            }
+            (Kind::Condition, None) => vec![0],
+            (Kind::Condition, Some(watch)) => {
+                let mut watched = vec![];
+                for name in watch {
+                    match args.iter().position(|arg| arg.name.as_ref() == Some(&name)) {
+                        Some(idx) => watched.push(idx),
+                        None => return Err(syn::Error::new(name.span(), format!("`{name}` is not an argument of `{fn_name}`"))),
+                    }
+                }
+                watched
+            }
+        };
+
+        // The other arguments become fields, which need a name
+        for (idx, input) in sig.inputs.iter().filter(|input| matches!(input, FnArg::Typed(_))).enumerate() {
+            if !watched.contains(&idx) && args[idx].name.is_none() {
+                return Err(syn::Error::new(input.span(), "use a plain name for this argument, it is stored in a field"));
+            }
+        }
+
+        // The generics of the impl block and the function, the stored values are sent to the node process
+        let mut generics = impl_generics.cloned().unwrap_or_default();
+        generics.params.extend(sig.generics.params.iter().cloned());
+        if let Some(where_clause) = &sig.generics.where_clause {
+            generics.make_where_clause().predicates.extend(where_clause.predicates.iter().cloned());
+        }
+        let type_params = generics.type_params().map(|param| param.ident.clone()).collect::<Vec<_>>();
+        let where_clause = generics.make_where_clause();
+        for param in type_params {
+            where_clause.predicates.push(parse_quote! { #param: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static });
+        }
+        let self_ty = item_impl.map(|item_impl| &*item_impl.self_ty);
+        if let (Some(self_ty), Receiver::Ref | Receiver::Value) = (self_ty, &receiver) {
+            where_clause.predicates.push(parse_quote! { #self_ty: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static });
+        }
+
+        // Checked here for a clearer error, the rest of the return type is checked by IntoResult
+        if let ReturnType::Default = sig.output {
+            return Err(syn::Error::new(sig.span(), format!("`{fn_name}` should return a bool or a Result<bool, E>")));
+        }
+
+        Ok(Leaf { kind, sig, self_ty, receiver, args, watched, generics, name: options.name })
+    }
+
+    // e.g. dock() gives DockExecutor, and Robot::dock() gives RobotDockExecutor
+    fn struct_name(&self) -> Ident {
+        let fn_name = self.sig.ident.to_string().to_case(Case::Pascal);
+        let type_name = match self.self_ty {
+            Some(Type::Path(path)) => path.path.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default(),
+            _ => String::new(),
+        };
+        Ident::new(&format!("{}{}{}", type_name, fn_name, self.kind.suffix()), self.sig.ident.span())
+    }
+
+    fn generate(&self) -> TokenStream {
+        let fn_name = &self.sig.ident;
+        let name_str = self.name.as_ref().map_or_else(|| fn_name.to_string(), LitStr::value);
+        let struct_name = self.struct_name();
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let (_, fn_ty_generics, _) = self.sig.generics.split_for_impl();
+        let turbofish = fn_ty_generics.as_turbofish();
+
+        // The object of a method is stored like the arguments
+        let mut fields = vec![];
+        let mut ctor_args = vec![];
+        let mut self_args = vec![];
+        if let (Some(self_ty), Receiver::Ref | Receiver::Value) = (self.self_ty, &self.receiver) {
+            let inner = format_ident!("inner");
+            fields.push(quote! { #inner: #self_ty });
+            ctor_args.push(quote! { #inner: #self_ty });
+            self_args.push(quote! { #inner });
+        }
+        for (_, arg) in self.args.iter().enumerate().filter(|(idx, _)| !self.watched.contains(idx)) {
+            let (name, ty) = (&arg.name, &arg.ty);
+            fields.push(quote! { #name: #ty });
+            ctor_args.push(quote! { #name: #ty });
+            self_args.push(quote! { #name });
+        }
+
+        // Type parameters that only show up in the watched values still need to be used by the struct
+        let type_params = self.generics.type_params().map(|param| &param.ident).collect::<Vec<_>>();
+        if !type_params.is_empty() {
+            fields.push(quote! { _marker: ::core::marker::PhantomData<fn() -> ( #( #type_params, )* )> });
+            self_args.push(quote! { _marker: ::core::marker::PhantomData });
+        }
+
+        // The watched values are passed in as a single value, or as a tuple in the order of watch
+        let bindings = self.watched.iter().map(|idx| format_ident!("watched_{}", idx)).collect::<Vec<_>>();
+        let watched_types = self.watched.iter().map(|idx| &self.args[*idx].ty).collect::<Vec<_>>();
+        let (handle_type, val_pattern) = match self.watched.len() {
+            1 => (quote! { #( #watched_types )* }, quote! { #( #bindings )* }),
+            _ => (quote! { ( #( #watched_types ),* ) }, quote! { ( #( #bindings ),* ) }),
+        };
+
+        // Passing the watched values and clone() of the fields to the original function
+        let call_args = self.args.iter().enumerate().map(|(idx, arg)| match self.watched.iter().position(|w| *w == idx) {
+            Some(pos) => {
+                let binding = &bindings[pos];
+                quote! { #binding }
+            }
+            None => {
+                let name = &arg.name;
+                quote! { self.#name.clone() }
+            }
+        });
+        let call = match (self.self_ty, &self.receiver) {
+            (None, _) => quote! { #fn_name #turbofish ( #( #call_args ),* ) },
+            (Some(self_ty), Receiver::None) => quote! { <#self_ty>::#fn_name #turbofish ( #( #call_args ),* ) },
+            (Some(_), Receiver::Ref) => quote! { self.inner.#fn_name #turbofish ( #( #call_args ),* ) },
+            (Some(_), Receiver::Value) => quote! { self.inner.clone().#fn_name #turbofish ( #( #call_args ),* ) },
+        };
+        let call = match self.sig.asyncness {
+            Some(_) => quote! { #call.await },
+            None => call,
+        };
+
+        // A plain bool can't fail, any error that converts into anyhow::Error is passed on
+        let result = quote! { ::behavior_tree::IntoResult::into_result(#call) };
+
+        let leaf_impl = match self.kind {
+            Kind::Action => quote! {
+                impl #impl_generics ::behavior_tree::Executor for #struct_name #ty_generics #where_clause {
+                    fn get_name(&self) -> ::std::string::String {
+                        #name_str.to_string()
+                    }
+
+                    async fn execute(&mut self) -> ::core::result::Result<bool, ::behavior_tree::anyhow::Error> {
+                        #result
+                    }
+                }
+            },
+            Kind::Condition => quote! {
+                impl #impl_generics ::behavior_tree::Evaluator<#handle_type> for #struct_name #ty_generics #where_clause {
+                    fn get_name(&self) -> ::std::string::String {
+                        #name_str.to_string()
+                    }
+
+                    async fn evaluate(&mut self, val: #handle_type) -> ::core::result::Result<bool, ::behavior_tree::anyhow::Error> {
+                        let #val_pattern = val;
+                        #result
+                    }
+                }
+            },
+        };
+
+        quote! {
+            #[derive(Clone)]
+            pub struct #struct_name #impl_generics #where_clause {
+                #( #fields ),*
+            }
+
+            #leaf_impl
+
+            impl #impl_generics #struct_name #ty_generics #where_clause {
+                pub fn new( #( #ctor_args ),* ) -> Self {
+                    Self { #( #self_args ),* }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,3 @@
+mod test_dsl;
+mod test_leaf;
+mod test_derive;
@@ -0,0 +1,34 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use proc_macro2::TokenStream;
+    use quote::quote;
+    use syn::DeriveInput;
+    use crate::derive::{Kind, expand};
+
+    // The message of the error the derive gives, it fails the test if the struct expands
+    fn error(kind: Kind, tokens: TokenStream) -> String {
+        match expand(kind, syn::parse2::<DeriveInput>(tokens).unwrap()) {
+            Ok(_) => panic!("the struct expanded without an error"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rejects_missing_method() {
+        let message = error(Kind::Executor, quote! { #[bt(name = "dock")] struct Dock {} });
+        assert_eq!(message, "add #[bt(execute = \"method\")] with the method to call");
+    }
+
+    #[test]
+    fn test_rejects_missing_value_type() {
+        let message = error(Kind::Evaluator, quote! { #[bt(evaluate = "check")] struct Above {} });
+        assert_eq!(message, "add #[bt(value = \"Type\")] with the type of the watched value");
+    }
+
+    #[test]
+    fn test_rejects_property_of_other_kind() {
+        let message = error(Kind::Evaluator, quote! { #[bt(evaluate = "check", value = "i32", halt = "stop")] struct Above {} });
+        assert_eq!(message, "unsupported bt property, expected `name`, `evaluate` or `value`");
+    }
+}
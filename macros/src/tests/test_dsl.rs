@@ -0,0 +1,79 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use proc_macro2::TokenStream;
+    use quote::quote;
+    use crate::dsl::TreeDef;
+
+    // The message of the error the tree gives, it fails the test if the tree parses
+    fn error(tokens: TokenStream) -> String {
+        match syn::parse2::<TreeDef>(tokens) {
+            Ok(_) => panic!("the tree parsed without an error"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parses_all_nodes() {
+        let tree = quote! {
+            "robot": seq {
+                fb_with_memory { action(a).name("a"), cond(handle, eval, options) },
+                weighted { 1.0 => success, 2.0 => failure },
+                utility("utility", handle, 1.0) { |x| *x => tree(subtree), |x| 1.0 - x => action(b) },
+                switch("mode", mode) { Mode::Dock => action(dock), _ => action_fn("idle", idle) },
+                cooldown(period) { rate_limit(2, period) { delay(period) { action_blocking(c) } } },
+            }
+        };
+        assert!(syn::parse2::<TreeDef>(tree).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_unknown_node() {
+        assert!(error(quote! { sequence { action(a) } }).starts_with("unknown node `sequence`"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_label() {
+        let message = error(quote! { action(a).label("a") });
+        assert_eq!(message, "unknown label `label`, expected name, description or meta");
+    }
+
+    #[test]
+    fn test_rejects_wrong_argument_count() {
+        assert_eq!(error(quote! { action(a, b) }), "`action` takes 1 arguments, found 2");
+        assert_eq!(error(quote! { cond(handle) }), "`cond` takes 2 or 3 arguments, found 1");
+        assert_eq!(error(quote! { seq(a) { success } }), "`seq` takes 0 arguments, found 1");
+        assert_eq!(error(quote! { rate_limit(2) { success } }), "`rate_limit` takes 2 arguments, found 1");
+        assert_eq!(error(quote! { action(a).meta("key") }), "`meta` takes 2 arguments, found 1");
+    }
+
+    #[test]
+    fn test_rejects_missing_children() {
+        assert_eq!(error(quote! { seq }), "`seq` expects its children as `{ .. }`");
+        assert_eq!(error(quote! { fb {} }), "`fb` needs at least one child");
+        assert_eq!(error(quote! { weighted }), "`weighted` expects its children as `{ weight => node, .. }`");
+        assert_eq!(error(quote! { switch("mode", mode) {} }), "`switch` needs at least one child");
+    }
+
+    #[test]
+    fn test_rejects_decorator_with_several_children() {
+        assert_eq!(error(quote! { run_once { success, failure } }), "`run_once` decorates a single child, found 2");
+    }
+
+    #[test]
+    fn test_rejects_children_of_leaf() {
+        assert_eq!(error(quote! { success { failure } }), "`success` is a leaf and has no children");
+    }
+
+    #[test]
+    fn test_rejects_several_switch_defaults() {
+        let message = error(quote! { switch("mode", mode) { _ => success, _ => failure } });
+        assert_eq!(message, "`switch` takes a single default `_ => node`, found 2");
+    }
+
+    #[test]
+    fn test_rejects_several_roots() {
+        let message = error(quote! { success, failure });
+        assert_eq!(message, "a tree has a single root, wrap the nodes in a composite like `seq { .. }`");
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use proc_macro2::TokenStream;
+    use quote::quote;
+    use crate::leaf::{Kind, expand};
+
+    // The message of the error the attribute gives, it fails the test if the item expands
+    fn error(kind: Kind, attr: TokenStream, item: TokenStream) -> String {
+        match expand(kind, attr, item) {
+            Ok(_) => panic!("the item expanded without an error"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_expands_impl_with_both_kinds() {
+        let item = quote! {
+            impl Robot {
+                #[bt_action]
+                async fn dock(&self) -> bool { true }
+
+                #[bt_condition(name = "battery")]
+                fn battery_ok(&self, battery: i32) -> bool { battery > 20 }
+            }
+        };
+        let expanded = expand(Kind::Action, quote! {}, item).unwrap().to_string();
+        assert!(expanded.contains("RobotDockExecutor"));
+        assert!(expanded.contains("RobotBatteryOkEvaluator"));
+    }
+
+    #[test]
+    fn test_second_impl_attribute_is_removed() {
+        let item = quote! {
+            #[bt_condition]
+            impl Robot {
+                #[bt_action]
+                fn dock() -> bool { true }
+            }
+        };
+        let expanded = expand(Kind::Action, quote! {}, item).unwrap().to_string();
+        assert!(!expanded.contains("bt_condition"));
+    }
+
+    #[test]
+    fn test_rejects_lifetime_parameters() {
+        let message = error(Kind::Action, quote! {}, quote! { fn say<'a>(text: &'a str) -> bool { true } });
+        assert_eq!(message, "`say` can't have lifetime parameters, its arguments are stored");
+    }
+
+    #[test]
+    fn test_rejects_impl_trait_arguments() {
+        let message = error(Kind::Action, quote! {}, quote! { fn say(text: impl Display) -> bool { true } });
+        assert_eq!(message, "use a generic parameter instead of `impl Trait`");
+    }
+
+    #[test]
+    fn test_rejects_receiver_without_marked_impl() {
+        let message = error(Kind::Action, quote! {}, quote! { fn dock(&self) -> bool { true } });
+        assert_eq!(message, "mark the impl block of `dock` with `#[bt_action]` as well");
+    }
+
+    #[test]
+    fn test_rejects_unnamed_stored_arguments() {
+        let message = error(Kind::Condition, quote! {}, quote! { fn above(value: i32, (low, high): (i32, i32)) -> bool { true } });
+        assert_eq!(message, "use a plain name for this argument, it is stored in a field");
+    }
+
+    #[test]
+    fn test_rejects_missing_return_type() {
+        let message = error(Kind::Action, quote! {}, quote! { fn dock() {} });
+        assert_eq!(message, "`dock` should return a bool or a Result<bool, E>");
+    }
+
+    #[test]
+    fn test_rejects_condition_without_watched_value() {
+        let message = error(Kind::Condition, quote! {}, quote! { fn ready() -> bool { true } });
+        assert_eq!(message, "`ready` needs an argument with the watched value");
+    }
+
+    #[test]
+    fn test_rejects_unknown_watched_argument() {
+        let message = error(Kind::Condition, quote! { watch = [speed] }, quote! { fn slow(value: i32) -> bool { true } });
+        assert_eq!(message, "`speed` is not an argument of `slow`");
+    }
+
+    #[test]
+    fn test_rejects_impl_without_marked_methods() {
+        let message = error(Kind::Action, quote! {}, quote! { impl Robot { fn dock() -> bool { true } } });
+        assert_eq!(message, "no method in here is marked with `#[bt_action]` or `#[bt_condition]`");
+    }
+
+    #[test]
+    fn test_rejects_options_on_impl() {
+        let message = error(Kind::Action, quote! { name = "robot" }, quote! { impl Robot { #[bt_action] fn dock() -> bool { true } } });
+        assert_eq!(message, "put the options of `bt_action` on the methods");
+    }
+}
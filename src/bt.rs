@@ -1,17 +1,21 @@
 use std::{collections::HashMap, marker::PhantomData};
 
-use actify::Handle;
+use tokio::sync::broadcast::{Receiver, Sender, channel};
+use tokio::time::Duration;
 use uuid::Uuid;
 
-use crate::{Action, Condition, execution::engine_factory::{Engine, EngineFactory, Engines}, nodes::{action::Executor, condition::Evaluator}, nodes_bin::{node::Node, node_map::NodeIdToProcessHandleMap}};
+use crate::{Action, Condition, Failure, Success, Wait, execution::{static_engine::{analyzer::{Finding, analyze}, optimizer::optimize}, debug_engine::debug_engine::Debugger, engine_factory::{Engine, EngineFactory, Engines}, tick_engine::tick_engine::Ticker, tree_events::TreeEvent, export::{TreeExport, export}, statistics::Statistics}, nodes::{action::{ClosureExecutor, Executor}, switch::Switch, utility::UtilitySelector, blocking::{BlockingAdapter, BlockingExecutor}, condition::{ConditionOptions, Evaluator}, watch::Watch}, nodes_bin::{node_arena::{NodeArena, NodeKind}, node_map::NodeIdToProcessHandleMap, process_handle::ProcessHandle}};
 
 pub(crate) const CHANNEL_SIZE: usize = 20;
+pub(crate) const EVENT_CHANNEL_SIZE: usize = 1024;
 
 pub struct BT<T: State> {
     name: String,
-    pub(crate) root: Node,
+    pub(crate) arena: NodeArena,
     pub(crate) map: NodeIdToProcessHandleMap,
+    pub(crate) events: Sender<TreeEvent>,
     engine_factory: EngineFactory,
+    pub(crate) seed: Option<u64>,
     result: Option<bool>,
     marker: PhantomData<T>,
 }
@@ -20,9 +24,11 @@ impl<T: State> BT<T> {
     fn into_state<S: State>(self) -> BT<S> {
         BT::<S> {
             name: self.name,
-            root: self.root,
+            arena: self.arena,
             map: self.map,
+            events: self.events,
             engine_factory: self.engine_factory,
+            seed: self.seed,
             result: self.result,
             marker: PhantomData,
         }
@@ -32,9 +38,11 @@ impl<T: State> BT<T> {
     pub fn test_into_state<S: State>(self) -> BT<S> {
         BT::<S> {
             name: self.name,
-            root: self.root,
+            arena: self.arena,
             map: self.map,
+            events: self.events,
             engine_factory: self.engine_factory,
+            seed: self.seed,
             result: self.result,
             marker: PhantomData,
         }
@@ -45,83 +53,229 @@ impl BT<Init> {
     pub fn new() -> BT<Preparing> {
         Self {
             name: "Unnamed Behavior Tree".to_string(),
-            root: Node::Sequence(vec![]), // Empty sequence as default
+            arena: NodeArena::leaf(NodeKind::Sequence), // Empty sequence as default
             map: HashMap::new(),
-            engine_factory: EngineFactory { engine: Engines::Dynamic },
+            events: channel(EVENT_CHANNEL_SIZE).0,
+            engine_factory: EngineFactory { engine: Engines::Dynamic, debug_session: None },
+            seed: None,
             result: None,
             marker: PhantomData,
         }.into_state::<Preparing>()
     }
 
     pub fn action<T: Executor + Send + Sync + 'static>(inner: T) -> BT<Builder>{
+        BT::action_process(Action::new(inner))
+    }
+
+    // Always succeeds, the analyzer knows its result up front
+    pub fn success() -> BT<Builder>{
+        BT::action_process(Success::new())
+    }
+
+    // Always fails, the analyzer knows its result up front
+    pub fn failure() -> BT<Builder>{
+        BT::action_process(Failure::new())
+    }
+
+    fn action_process(handle: ProcessHandle) -> BT<Builder>{
         let uid = Uuid::new_v4();
-        let root = Node::Action(uid.into());
+        let arena = NodeArena::leaf(NodeKind::Action(uid.into()));
         let mut map = HashMap::new();
-        map.insert(uid.into(), Action::new(inner));
+        map.insert(uid.into(), handle);
         
         let mut bt = BT::new();
-        bt.root = root;
+        bt.arena = arena;
         bt.map = map;
         bt.into_state::<Builder>()
     }
 
-    pub fn condition<V,T>(handle: Handle<V>, inner: T) -> BT<Builder> 
+    pub fn action_blocking<T: BlockingExecutor + Send + 'static>(inner: T) -> BT<Builder>{
+        BT::action(BlockingAdapter::new(inner, None))
+    }
+
+    pub fn action_fn<S, F, Fut>(name: S, function: F) -> BT<Builder>
+    where
+        S: Into<String>,
+        F: FnMut() -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = anyhow::Result<bool>> + Send,
+    {
+        BT::action(ClosureExecutor::new(name.into(), function))
+    }
+
+    pub fn condition<W,T>(handle: W, inner: T) -> BT<Builder> 
+    where
+        W: Watch,
+        T: Evaluator<W::Value> + Sync + Send + Clone + 'static,
+    {
+        BT::condition_with(handle, inner, ConditionOptions::default())
+    }
+
+    pub fn condition_with<W,T>(handle: W, inner: T, options: ConditionOptions) -> BT<Builder> 
     where
-        V: Clone + std::fmt::Debug + Send + Sync + 'static,
-        T: Evaluator<V> + Sync + Send + Clone + 'static,
+        W: Watch,
+        T: Evaluator<W::Value> + Sync + Send + Clone + 'static,
     {
         let uid = Uuid::new_v4();
-        let root = Node::Condition(uid.into());
+        let arena = NodeArena::leaf(NodeKind::Condition(uid.into()));
         let mut map = HashMap::new();
-        map.insert(uid.into(), Condition::new_from(inner, handle));
+        map.insert(uid.into(), Condition::new_with(inner, handle, options));
         
         let mut bt = BT::new();
-        bt.root = root;
+        bt.arena = arena;
         bt.map = map;
         bt.into_state::<Builder>()
     }
 
+    // Conditions in the given tree are evaluated once when they are reached, and not monitored afterwards
+    pub fn checked_once(mut tree: BT<Builder>) -> BT<Builder> {
+        tree.arena.set_checked_once();
+        tree
+    }
+
     pub fn seq(children: Vec<BT<Builder>>) -> BT<Builder>{
-        let mut map = HashMap::new();
-        let mut node_children = vec![];
-        for child in children {
-            map.extend(child.map);
-            node_children.push(child.root);
-        }
-        let root = Node::Sequence(node_children);
-        
-        let mut bt = BT::new();
-        bt.root = root;
-        bt.map = map;
-        bt.into_state::<Builder>()
+        BT::composite(NodeKind::Sequence, children)
     }
 
     pub fn fb(children: Vec<BT<Builder>>) -> BT<Builder>{
+        BT::composite(NodeKind::Fallback, children)
+    }
+
+    // Sequence that resumes at its first unfinished child when a condition trigger re-enters it.
+    // The succeeded children are remembered until the sequence finishes or the tree run ends
+    pub fn seq_with_memory(children: Vec<BT<Builder>>) -> BT<Builder>{
+        BT::composite(NodeKind::SequenceWithMemory, children)
+    }
+
+    // Fallback that remembers its failed children, with the same reset rules as seq_with_memory()
+    pub fn fb_with_memory(children: Vec<BT<Builder>>) -> BT<Builder>{
+        BT::composite(NodeKind::FallbackWithMemory, children)
+    }
+
+    // Sequence over the children in a random order, drawn each time it is entered
+    pub fn random_seq(children: Vec<BT<Builder>>) -> BT<Builder>{
+        BT::composite(NodeKind::RandomSequence, children)
+    }
+
+    // Fallback over the children in a random order, drawn each time it is entered
+    pub fn random_fb(children: Vec<BT<Builder>>) -> BT<Builder>{
+        BT::composite(NodeKind::RandomFallback, children)
+    }
+
+    // Runs one child, picked with a chance proportional to its weight, and returns its result
+    pub fn weighted(children: Vec<(f64, BT<Builder>)>) -> BT<Builder>{
+        let children = children.into_iter().map(|(weight, mut child)| {
+            child.arena.set_weight(weight);
+            child
+        }).collect();
+        BT::composite(NodeKind::WeightedChoice, children)
+    }
+
+    // Runs the child with the highest score and returns its result. It is a switch on the scores, so a change of
+    // the scores stops the running child and starts the new one, like a switch does for a new value
+    pub fn utility<W: Watch>(selector: UtilitySelector<W>) -> BT<Builder>{
+        let uid = Uuid::new_v4();
+        let (process, children) = selector.into_parts();
+        let mut bt = BT::composite(NodeKind::Switch(uid.into()), children);
+        bt.map.insert(uid.into(), process);
+        bt
+    }
+
+    // Runs the branch selected by the value of the handle and returns its result. The switch stays monitored,
+    // so a new value stops the running branch and starts the selected one
+    pub fn switch<W>(switch: Switch<W>) -> BT<Builder>
+    where
+        W: Watch,
+        W::Value: PartialEq,
+    {
+        let uid = Uuid::new_v4();
+        let (process, children) = switch.into_parts();
+        let mut bt = BT::composite(NodeKind::Switch(uid.into()), children);
+        bt.map.insert(uid.into(), process);
+        bt
+    }
+
+    // The decorators below decide again each time they are entered. Their timers and results last for one
+    // run() of the event engines, and across the runs of a Ticker until Ticker::reset is called.
+    // Runs the child the first time it is reached, afterwards returns the same result without running it
+    pub fn run_once(child: BT<Builder>) -> BT<Builder>{
+        BT::composite(NodeKind::RunOnce, vec![child])
+    }
+
+    // Fails without running the child until the period passed since the child last finished
+    pub fn cooldown(period: Duration, child: BT<Builder>) -> BT<Builder>{
+        BT::composite(NodeKind::Cooldown(period), vec![child])
+    }
+
+    // Fails without running the child if it was started `limit` times within the last period
+    pub fn rate_limit(limit: u32, period: Duration, child: BT<Builder>) -> BT<Builder>{
+        BT::composite(NodeKind::RateLimit(limit, period), vec![child])
+    }
+
+    // Waits for the period before it starts the child. Unlike a Wait action in front of it, a condition
+    // trigger that leaves the node during the wait stops it, and entering it again waits the full period
+    pub fn delay(period: Duration, child: BT<Builder>) -> BT<Builder>{
+        let uid = Uuid::new_v4();
+        let mut bt = BT::composite(NodeKind::Delay(uid.into()), vec![child]);
+        bt.map.insert(uid.into(), Wait::named("Delay", period));
+        bt
+    }
+
+    // Restarts the child every time it succeeds, as in a while-success loop. Fails when the child fails.
+    // Every iteration starts the nodes below it over, e.g. a sequence with memory below it starts from its first child
+    pub fn keep_running_until_failure(child: BT<Builder>) -> BT<Builder>{
+        BT::composite(NodeKind::KeepRunningUntilFailure, vec![child])
+    }
+
+    fn composite(kind: NodeKind, children: Vec<BT<Builder>>) -> BT<Builder>{
         let mut map = HashMap::new();
         let mut node_children = vec![];
         for child in children {
             map.extend(child.map);
-            node_children.push(child.root);
+            node_children.push(child.arena);
         }
-        let root = Node::Fallback(node_children);
+        let arena = NodeArena::composite(kind, node_children);
         
         let mut bt = BT::new();
-        bt.root = root;
+        bt.arena = arena;
         bt.map = map;
         bt.into_state::<Builder>()
     }
 }
 
+// Shown in tree events, the debugger, logs, exports and statistics. Applies to the root node of the subtree, so
+// BT::seq(..).name("patrol") names the sequence and BT::action(..).name("dock") overrides the executor name
+impl BT<Builder> {
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        // The process of a leaf logs with the name as well
+        if let Some(handle) = self.arena.get_id(self.arena.root()).and_then(|id| self.map.get(id)) {
+            handle.rename(&name);
+        }
+        self.arena.root_meta_mut().name = Some(name);
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.arena.root_meta_mut().description = Some(description.into());
+        self
+    }
+
+    pub fn meta(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.arena.root_meta_mut().metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
 impl BT<Preparing> {
     pub fn root(mut self, tree: BT<Builder>) -> BT<Ready> {
-        self.root = tree.root;
+        self.arena = tree.arena;
         self.map = tree.map;
         self.into_state::<Ready>()
     }
 
     #[cfg(test)]
-    pub fn test_root(mut self, root: Node) -> BT<Ready> {
-        self.root = root;
+    pub fn test_root(mut self, root: crate::nodes_bin::node::Node) -> BT<Ready> {
+        self.arena = root.into();
         self.into_state::<Ready>()
     }
 
@@ -142,14 +296,60 @@ impl<T: NotDone> BT<T> {
         self.engine_factory.set(engine);
         self
     }
+
+    // Seeds the random composites, so runs with the same seed make the same choices
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    // Status changes of the nodes during the run
+    pub fn subscribe(&self) -> Receiver<TreeEvent> {
+        self.events.subscribe()
+    }
 }
 
 impl BT<Ready> {
     pub async fn run(mut self) -> BT<Done> {
-        let mut engine = self.engine_factory.create(&self);
+        let debug_session = self.engine_factory.debug_session.take();
+        let mut engine = self.engine_factory.create(&self, debug_session);
         self.result = Some(engine.run().await);
         self.into_state::<Done>()
     }
+
+    // Pauses the run before every node start and condition trigger until released by the debugger
+    pub fn debugger(&mut self) -> Debugger {
+        let (debugger, session) = Debugger::new();
+        self.engine_factory.debug_session = Some(session);
+        debugger
+    }
+
+    // Hands the tree over to be ticked manually instead of run by an engine. The engine chosen with
+    // set_engine() is not used, the caller decides when to tick. Dropping the ticker kills the nodes
+    pub fn into_ticker(self) -> Ticker {
+        Ticker::new(&self)
+    }
+
+    // Structural problems like unreachable nodes and children that can never run, found without running the tree
+    pub fn analyze(&self) -> Vec<Finding> {
+        analyze(self)
+    }
+
+    // Nodes with their names, descriptions and metadata, nested like the tree
+    pub fn export(&self) -> TreeExport {
+        export(self, &self.name)
+    }
+
+    // Starts counting the starts, results, halts and running time of the nodes from the tree events.
+    // Take it from the tree that runs, after optimize()
+    pub fn statistics(&self) -> Statistics {
+        Statistics::new(self.events.subscribe())
+    }
+
+    // Equivalent tree with fewer nodes to traverse, e.g. Sequence([Sequence([a, b]), Success, c]) becomes Sequence([a, b, c])
+    pub fn optimize(self) -> BT<Ready> {
+        optimize(self)
+    }
 }
 
 impl BT<Done> {
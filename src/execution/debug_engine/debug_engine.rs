@@ -0,0 +1,225 @@
+use std::sync::{Arc, Mutex};
+
+use log::debug;
+use tokio::sync::{mpsc, watch};
+
+use crate::bt::Ready;
+use crate::execution::engine_factory::{Engine, EventEngine};
+use crate::execution::process_comms::FutureVec;
+use crate::nodes_bin::node_arena::{NodeArena, NodeIndex};
+use crate::nodes_bin::node_info::NodeInfo;
+use crate::BT;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    Id(String),
+    Name(String),
+}
+
+impl Breakpoint {
+    fn matches(&self, node: &NodeInfo) -> bool {
+        match self {
+            Breakpoint::Id(id) => node.id.as_ref() == Some(id),
+            Breakpoint::Name(name) => node.name == *name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PausePoint {
+    // Before ChildMessage::Start is sent to the node
+    Start(NodeInfo),
+    // Before the engine handles a monitored condition that switched its result
+    ConditionTrigger(NodeInfo, bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugState {
+    pub pause: usize, // Number of pauses so far in this run
+    pub paused_at: PausePoint,
+    pub trace: Vec<NodeInfo>, // From the root down to the current node
+    pub active_conditions: Vec<NodeInfo>,
+}
+
+enum DebugCommand {
+    Step,
+    Continue,
+}
+
+// The engine side of a debugger
+pub(crate) struct DebugSession {
+    commands: mpsc::UnboundedReceiver<DebugCommand>,
+    state: watch::Sender<Option<DebugState>>,
+    breakpoints: Arc<Mutex<Vec<Breakpoint>>>,
+}
+
+// Controls an engine from another task. The engine starts paused before its first node
+#[derive(Clone)]
+pub struct Debugger {
+    commands: mpsc::UnboundedSender<DebugCommand>,
+    state: watch::Receiver<Option<DebugState>>,
+    breakpoints: Arc<Mutex<Vec<Breakpoint>>>,
+    released: usize,
+}
+
+impl Debugger {
+    pub(crate) fn new() -> (Debugger, DebugSession) {
+        let (commands_tx, commands_rx) = mpsc::unbounded_channel();
+        let (state_tx, state_rx) = watch::channel(None);
+        let breakpoints = Arc::new(Mutex::new(vec![]));
+
+        let debugger = Self {
+            commands: commands_tx,
+            state: state_rx,
+            breakpoints: breakpoints.clone(),
+            released: 0,
+        };
+        let session = DebugSession {
+            commands: commands_rx,
+            state: state_tx,
+            breakpoints,
+        };
+        (debugger, session)
+    }
+
+    // Waits until the engine pauses, returns None once the tree is finished
+    pub async fn wait_paused(&mut self) -> Option<DebugState> {
+        let released = self.released;
+        let state = self.state
+            .wait_for(|state| state.as_ref().is_some_and(|s| s.pause > released))
+            .await
+            .ok()?;
+        state.clone()
+    }
+
+    // The state of the engine if it is currently paused
+    pub fn state(&self) -> Option<DebugState> {
+        self.state.borrow().clone()
+    }
+
+    // Lets the engine pass the current pause, it pauses again at the next start or trigger
+    pub async fn step(&mut self) {
+        self.release(DebugCommand::Step).await
+    }
+
+    // Lets the engine run until the next breakpoint
+    pub async fn resume(&mut self) {
+        self.release(DebugCommand::Continue).await
+    }
+
+    pub fn add_breakpoint(&self, breakpoint: Breakpoint) {
+        self.breakpoints.lock().unwrap().push(breakpoint);
+    }
+
+    pub fn remove_breakpoint(&self, breakpoint: &Breakpoint) {
+        self.breakpoints.lock().unwrap().retain(|b| b != breakpoint);
+    }
+
+    async fn release(&mut self, command: DebugCommand) {
+        if let Some(state) = self.wait_paused().await {
+            self.released = state.pause;
+            let _ = self.commands.send(command);
+        }
+    }
+}
+
+// Wraps an event-based engine and pauses it on the request of a Debugger
+pub(crate) struct DebugEngine<E: EventEngine> {
+    engine: E,
+    nodes: Vec<NodeInfo>, // Indexed by NodeIndex
+    session: DebugSession,
+    stepping: bool,
+    pauses: usize,
+}
+
+impl<E: EventEngine> DebugEngine<E> {
+    pub(crate) fn new(tree: &BT<Ready>, engine: E, session: DebugSession) -> DebugEngine<E> {
+        Self {
+            engine,
+            nodes: NodeInfo::collect(&tree.arena, &tree.map),
+            session,
+            stepping: true, // Pause before the first node
+            pauses: 0,
+        }
+    }
+
+    async fn pause(&mut self, paused_at: PausePoint) {
+        let node = match &paused_at {
+            PausePoint::Start(node) | PausePoint::ConditionTrigger(node, _) => node,
+        };
+        let at_breakpoint = self.session.breakpoints.lock().unwrap().iter().any(|b| b.matches(node));
+        if !self.stepping && !at_breakpoint {
+            return;
+        }
+
+        debug!("Debugger paused at {:?}", paused_at);
+        self.pauses += 1;
+        let state = DebugState {
+            pause: self.pauses,
+            paused_at,
+            trace: self.engine.arena().trace(self.engine.current_node()).into_iter().map(|idx| self.node(idx)).collect(),
+            active_conditions: self.engine.active_conditions().iter().map(|idx| self.node(*idx)).collect(),
+        };
+        self.session.state.send_replace(Some(state));
+
+        self.stepping = match self.session.commands.recv().await {
+            Some(DebugCommand::Step) => true,
+            Some(DebugCommand::Continue) => false,
+            None => false, // All debuggers are dropped
+        };
+        self.session.state.send_replace(None);
+    }
+
+    fn node(&self, idx: NodeIndex) -> NodeInfo {
+        self.nodes[idx.index()].clone()
+    }
+}
+
+// Forwards the steps to the wrapped engine, and pauses in the hooks
+impl<E: EventEngine> EventEngine for DebugEngine<E> {
+    fn arena(&self) -> &NodeArena {
+        self.engine.arena()
+    }
+
+    fn current_node(&self) -> NodeIndex {
+        self.engine.current_node()
+    }
+
+    fn active_conditions(&self) -> &[NodeIndex] {
+        self.engine.active_conditions()
+    }
+
+    async fn start_current_node(&mut self) {
+        self.engine.start_current_node().await
+    }
+
+    fn build_listener_futures<'a>(&'a mut self) -> FutureVec<'a> {
+        self.engine.build_listener_futures()
+    }
+
+    async fn handle_current_node_finished(&mut self, status: bool) -> Option<bool> {
+        self.engine.handle_current_node_finished(status).await
+    }
+
+    async fn handle_condition_trigger(&mut self, node: NodeIndex, status: bool, index: usize) -> Option<bool> {
+        self.engine.handle_condition_trigger(node, status, index).await
+    }
+
+    async fn handle_branch_change(&mut self, node: NodeIndex, branch: usize, index: usize) -> Option<bool> {
+        self.engine.handle_branch_change(node, branch, index).await
+    }
+
+    async fn before_start(&mut self) {
+        self.pause(PausePoint::Start(self.node(self.engine.current_node()))).await
+    }
+
+    async fn before_trigger(&mut self, node: NodeIndex, status: bool) {
+        self.pause(PausePoint::ConditionTrigger(self.node(node), status)).await
+    }
+}
+
+impl<E: EventEngine> Engine for DebugEngine<E> {
+    async fn run(&mut self) -> bool {
+        self.run_events().await
+    }
+}
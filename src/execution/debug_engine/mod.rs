@@ -0,0 +1 @@
+pub(crate) mod debug_engine;
\ No newline at end of file
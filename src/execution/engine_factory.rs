@@ -1,20 +1,95 @@
-use crate::{BT, bt::Ready, execution::{dynamic_engine::dynamic_engine::DynamicEngine, static_engine::static_engine::StaticEngine}};
+use std::time::Duration;
+
+use futures::future::select_all;
+use log::{error, trace, warn};
+
+use crate::{BT, bt::Ready, execution::{debug_engine::debug_engine::{DebugEngine, DebugSession}, dynamic_engine::dynamic_engine::DynamicEngine, process_comms::FutureVec, static_engine::static_engine::StaticEngine, tick_engine::tick_engine::TickEngine}, nodes_bin::{node_arena::{NodeArena, NodeIndex}, node_message::FutResult}};
 
 pub(crate) trait Engine {
     async fn run(&mut self) -> bool;
 }
 
+// Steps of the event-based engines, driven by the loop in run_events(). A wrapper like the
+// debugger implements the steps by forwarding them, and pauses the loop in the hooks
+pub(crate) trait EventEngine {
+    fn arena(&self) -> &NodeArena;
+    fn current_node(&self) -> NodeIndex;
+    fn active_conditions(&self) -> &[NodeIndex];
+    async fn start_current_node(&mut self);
+    fn build_listener_futures<'a>(&'a mut self) -> FutureVec<'a>;
+    async fn handle_current_node_finished(&mut self, status: bool) -> Option<bool>;
+    async fn handle_condition_trigger(&mut self, node: NodeIndex, status: bool, index: usize) -> Option<bool>;
+    async fn handle_branch_change(&mut self, node: NodeIndex, branch: usize, index: usize) -> Option<bool>;
+
+    // Before ChildMessage::Start is sent to the current node
+    async fn before_start(&mut self) {}
+
+    // Before a monitored condition or switch that changed is handled
+    async fn before_trigger(&mut self, _node: NodeIndex, _status: bool) {}
+
+    async fn run_events(&mut self) -> bool {
+        let mut start = true;
+        loop {
+            if !self.arena().has_process(self.current_node()) {
+                warn!("Not Running Empty Selector");
+                return false;
+            }
+
+            if start {
+                self.before_start().await;
+                self.start_current_node().await;
+            }
+
+            let futures: FutureVec = self.build_listener_futures();
+            if futures.is_empty() {
+                error!("Zero listener futures in engine!"); // This should not happen
+                return false;
+            }
+
+            let (result, index, _) = select_all(futures).await;
+            trace!("Future with index {:?} returned: {:?}", index, result);
+
+            let previous = self.current_node();
+            let finished = matches!(result, FutResult::CurrentNode(_));
+            if let Some(res) = match result {
+                // Current node finished
+                FutResult::CurrentNode(res) => self.handle_current_node_finished(res).await,
+                // Previous condition switched
+                FutResult::Condition(node, status) => {
+                    self.before_trigger(node, status).await;
+                    self.handle_condition_trigger(node, status, index).await
+                }
+                // Previous switch selected another branch
+                FutResult::Branch(node, branch) => {
+                    self.before_trigger(node, true).await;
+                    self.handle_branch_change(node, branch, index).await
+                }
+            } {
+                return res;
+            }
+            // A trigger that selected the running node again leaves it running
+            start = finished || self.current_node() != previous;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Engines {
     // Event-based, creates a map (node, result) -> next node
     Static,
     // Event-based, looks up next node during run time
     Dynamic,
+    // Tick-based, walks the tree from the root once per given period
+    Tick(Duration),
 }
 
 // Wrapper for the factory
 pub enum EngineDispatch {
     Static(StaticEngine),
     Dynamic(DynamicEngine),
+    Tick(TickEngine),
+    DebugStatic(DebugEngine<StaticEngine>),
+    DebugDynamic(DebugEngine<DynamicEngine>),
 }
 
 impl Engine for EngineDispatch {
@@ -22,12 +97,16 @@ impl Engine for EngineDispatch {
         match self {
             EngineDispatch::Static(e)  => e.run().await,
             EngineDispatch::Dynamic(e) => e.run().await,
+            EngineDispatch::Tick(e) => e.run().await,
+            EngineDispatch::DebugStatic(e) => e.run().await,
+            EngineDispatch::DebugDynamic(e) => e.run().await,
         }
     }
 }
 
 pub(crate) struct EngineFactory {
     pub engine: Engines,
+    pub debug_session: Option<DebugSession>,
 }
 
 impl EngineFactory {
@@ -35,10 +114,17 @@ impl EngineFactory {
         self.engine = engine
     }
 
-    pub(crate) fn create(&self, tree: &BT<Ready>) -> EngineDispatch {
-        match self.engine {
-            Engines::Static => EngineDispatch::Static(StaticEngine::new(tree)),
-            Engines::Dynamic => EngineDispatch::Dynamic(DynamicEngine::new(tree)),
+    pub(crate) fn create(&self, tree: &BT<Ready>, debug_session: Option<DebugSession>) -> EngineDispatch {
+        match (self.engine, debug_session) {
+            (Engines::Static, None) => EngineDispatch::Static(StaticEngine::new(tree)),
+            (Engines::Dynamic, None) => EngineDispatch::Dynamic(DynamicEngine::new(tree)),
+            (Engines::Tick(period), None) => EngineDispatch::Tick(TickEngine::new(tree, period)),
+            (Engines::Static, Some(session)) => EngineDispatch::DebugStatic(DebugEngine::new(tree, StaticEngine::new(tree), session)),
+            (Engines::Dynamic, Some(session)) => EngineDispatch::DebugDynamic(DebugEngine::new(tree, DynamicEngine::new(tree), session)),
+            (Engines::Tick(period), Some(_)) => {
+                warn!("The debugger only supports event-based engines, running without it");
+                EngineDispatch::Tick(TickEngine::new(tree, period))
+            }
         }
     }
 }
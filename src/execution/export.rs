@@ -0,0 +1,52 @@
+use serde::Serialize;
+
+use crate::{BT, bt::Ready, nodes_bin::{node_arena::{NodeArena, NodeIndex, NodeKind}, node_info::NodeInfo}};
+
+// Structure of a tree with the names, descriptions and metadata of its nodes, e.g. to serialize for a viewer
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TreeExport {
+    pub name: String,
+    pub root: ExportedNode,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportedNode {
+    #[serde(flatten)]
+    pub node: NodeInfo,
+    pub kind: String,
+    pub children: Vec<ExportedNode>,
+}
+
+pub(crate) fn export(bt: &BT<Ready>, name: &str) -> TreeExport {
+    let infos = NodeInfo::collect(&bt.arena, &bt.map);
+    TreeExport { name: name.to_string(), root: exported(&bt.arena, &infos, bt.arena.root()) }
+}
+
+fn exported(arena: &NodeArena, infos: &[NodeInfo], idx: NodeIndex) -> ExportedNode {
+    ExportedNode {
+        node: infos[idx.index()].clone(),
+        kind: kind(arena.kind(idx)).to_string(),
+        children: arena.children(idx).iter().map(|child| exported(arena, infos, *child)).collect(),
+    }
+}
+
+// Without the ids and parameters, the ids are part of the node info already
+fn kind(kind: &NodeKind) -> &'static str {
+    match kind {
+        NodeKind::Action(_) => "Action",
+        NodeKind::Condition(_) => "Condition",
+        NodeKind::Sequence => "Sequence",
+        NodeKind::Fallback => "Fallback",
+        NodeKind::SequenceWithMemory => "SequenceWithMemory",
+        NodeKind::FallbackWithMemory => "FallbackWithMemory",
+        NodeKind::RandomSequence => "RandomSequence",
+        NodeKind::RandomFallback => "RandomFallback",
+        NodeKind::WeightedChoice => "WeightedChoice",
+        NodeKind::Switch(_) => "Switch",
+        NodeKind::RunOnce => "RunOnce",
+        NodeKind::Cooldown(_) => "Cooldown",
+        NodeKind::RateLimit(..) => "RateLimit",
+        NodeKind::Delay(_) => "Delay",
+        NodeKind::KeepRunningUntilFailure => "KeepRunningUntilFailure",
+    }
+}
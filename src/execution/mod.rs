@@ -2,4 +2,9 @@ pub(super) mod static_engine;
 pub(super) mod engine_factory;
 pub(super) mod traversal;
 pub(super) mod dynamic_engine;
+pub(super) mod tick_engine;
+pub(super) mod debug_engine;
+pub(super) mod tree_events;
+pub(super) mod export;
+pub(super) mod statistics;
 mod process_comms;
\ No newline at end of file
@@ -1,30 +1,38 @@
 use std::pin::Pin;
 
-use crate::nodes_bin::{node_error::NodeError, node_map::NodeIdToProcessHandleMap, node_message::{ChildMessage, FutResult}, process_handle::ProcessHandle};
+use crate::nodes_bin::{node_arena::{NodeArena, NodeIndex}, node_error::NodeError, node_map::NodeIdToProcessHandleMap, node_message::{ChildMessage, FutResult}, process_handle::ProcessHandle};
 
 // Shorten Future type
 pub type FutureVec<'a> = Vec<Pin<Box<dyn Future<Output = FutResult> + Send + 'a>>>;
 
 pub(super) struct ProcessComms {
-    map: NodeIdToProcessHandleMap
+    handles: Vec<Option<ProcessHandle>>, // Indexed by NodeIndex, None for composite nodes
 }
 
 impl ProcessComms {
-    pub fn new(map: NodeIdToProcessHandleMap) -> ProcessComms {
-        Self { map }
+    pub fn new(arena: &NodeArena, map: &NodeIdToProcessHandleMap) -> ProcessComms {
+        let handles = arena
+            .indices()
+            .map(|idx| arena.get_id(idx).and_then(|id| map.get(id)).cloned())
+            .collect();
+        Self { handles }
     }
 
-    pub async fn send(&mut self, id: String, msg: ChildMessage) -> Result<(), NodeError>{
-        if let Some(handle) = self.map.get_mut(&id) {
-            return handle.send(msg).await
+    pub async fn send(&mut self, node: NodeIndex, msg: ChildMessage) -> Result<(), NodeError>{
+        self.get_handle(node)?.send(msg).await
+    }
+
+    // Kills all processes without waiting for them to exit
+    pub fn kill_all_now(&self) {
+        for handle in self.handles.iter().flatten() {
+            handle.kill_now();
         }
-        Err(NodeError::ExecutionError("No process found!".to_string()))
     }
 
-    pub fn get_handle(&mut self, id: String) -> Result<&mut ProcessHandle, NodeError>{
-        if let Some(handle) = self.map.get_mut(&id) {
+    pub fn get_handle(&mut self, node: NodeIndex) -> Result<&mut ProcessHandle, NodeError>{
+        if let Some(Some(handle)) = self.handles.get_mut(node.index()) {
             return Ok(handle);
         }
         Err(NodeError::ExecutionError("No process found!".to_string()))
     }
-}
\ No newline at end of file
+}
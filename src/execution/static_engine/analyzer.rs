@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use crate::{BT, bt::Ready, execution::static_engine::converter::{explore, leaves}, nodes_bin::{node_arena::{NodeIndex, NodeKind}, node_info::{NodeInfo, NodeMeta}, node_status::Status}};
+
+// A problem in the structure of a tree, found without running it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Finding {
+    Unreachable(NodeInfo), // No run ever starts it, only reported for the topmost node of such a subtree
+    DeadChildren { fallback: NodeInfo, child: NodeInfo }, // The child always succeeds, so the children after it never run
+    AlwaysFails { sequence: NodeInfo, child: NodeInfo }, // The child always fails, so the sequence never succeeds
+    EmptyComposite(NodeInfo), // Fails right away when it is reached
+    Flattenable { parent: NodeInfo, child: NodeInfo }, // Same kind as its parent, its children can take its place
+}
+
+// Findings ordered by the node they are about, from the root down
+pub(crate) fn analyze(bt: &BT<Ready>) -> Vec<Finding> {
+    let arena = &bt.arena;
+    let infos = NodeInfo::collect(arena, &bt.map);
+    let info = |idx: NodeIndex| infos[idx.index()].clone();
+
+    // A leaf that always returns the same result only continues with that result
+    let (map, _) = explore(bt, |node, status| constant(bt, node).is_none_or(|result| result == status));
+    let started: HashSet<_> = map.keys().map(|(node, _)| *node).collect();
+    let reached = |idx: NodeIndex| leaves(bt, idx).iter().any(|leaf| started.contains(leaf));
+
+    let mut findings = vec![];
+    for idx in arena.indices() {
+        let children = arena.children(idx);
+        let kind = arena.kind(idx);
+
+        // Subtrees without any leaf are empty composites, those are reported below
+        let parent_reached = arena.parent(idx).is_none_or(reached);
+        if parent_reached && !leaves(bt, idx).is_empty() && !reached(idx) {
+            findings.push(Finding::Unreachable(info(idx)));
+        }
+
+        if is_composite(kind) && children.is_empty() {
+            findings.push(Finding::EmptyComposite(info(idx)));
+        }
+
+        match kind {
+            NodeKind::Fallback | NodeKind::FallbackWithMemory => {
+                let succeeds = children.iter().position(|child| outcome(bt, *child) == Some(Status::Success));
+                if let Some(pos) = succeeds.filter(|pos| pos + 1 < children.len()) {
+                    findings.push(Finding::DeadChildren { fallback: info(idx), child: info(children[pos]) });
+                }
+            }
+            NodeKind::Sequence | NodeKind::SequenceWithMemory | NodeKind::RandomSequence => {
+                if let Some(child) = children.iter().find(|child| outcome(bt, **child) == Some(Status::Failure)) {
+                    findings.push(Finding::AlwaysFails { sequence: info(idx), child: info(*child) });
+                }
+            }
+            _ => (),
+        }
+
+        // A named child is kept, flattening it would lose the name
+        if matches!(kind, NodeKind::Sequence | NodeKind::Fallback) {
+            for child in children.iter().filter(|child| arena.kind(**child) == kind && arena.get(**child).meta == NodeMeta::default()) {
+                findings.push(Finding::Flattenable { parent: info(idx), child: info(*child) });
+            }
+        }
+    }
+    findings
+}
+
+// Result of an action that always returns the same
+pub(crate) fn constant(bt: &BT<Ready>, node: NodeIndex) -> Option<Status> {
+    match bt.arena.kind(node) {
+        NodeKind::Action(id) => bt.map.get(id).and_then(|handle| handle.constant()).map(|result| result.into()),
+        _ => None,
+    }
+}
+
+// Result of a subtree that is the same on every run, if it is
+pub(crate) fn outcome(bt: &BT<Ready>, node: NodeIndex) -> Option<Status> {
+    let children = bt.arena.children(node);
+    let results = children.iter().map(|child| outcome(bt, *child)).collect::<Vec<_>>();
+    let any = |status: Status| results.contains(&Some(status));
+    let all = |status: Status| !results.is_empty() && results.iter().all(|result| *result == Some(status));
+
+    match bt.arena.kind(node) {
+        NodeKind::Action(_) => constant(bt, node),
+        NodeKind::Sequence | NodeKind::SequenceWithMemory | NodeKind::RandomSequence if any(Status::Failure) => Some(Status::Failure),
+        NodeKind::Sequence | NodeKind::SequenceWithMemory | NodeKind::RandomSequence if all(Status::Success) => Some(Status::Success),
+        NodeKind::Fallback | NodeKind::FallbackWithMemory | NodeKind::RandomFallback if any(Status::Success) => Some(Status::Success),
+        NodeKind::Fallback | NodeKind::FallbackWithMemory | NodeKind::RandomFallback if all(Status::Failure) => Some(Status::Failure),
+        NodeKind::WeightedChoice if all(Status::Success) || all(Status::Failure) => results[0],
+        NodeKind::RunOnce | NodeKind::Delay(_) => results[0],
+        // Blocked decorators fail as well, and a loop only ends when its child fails
+        NodeKind::Cooldown(_) | NodeKind::RateLimit(..) | NodeKind::KeepRunningUntilFailure if any(Status::Failure) => Some(Status::Failure),
+        _ => None,
+    }
+}
+
+fn is_composite(kind: &NodeKind) -> bool {
+    matches!(kind,
+        NodeKind::Sequence | NodeKind::Fallback | NodeKind::SequenceWithMemory | NodeKind::FallbackWithMemory |
+        NodeKind::RandomSequence | NodeKind::RandomFallback | NodeKind::WeightedChoice |
+        NodeKind::Switch(_))
+}
@@ -1,39 +1,70 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 
-use crate::{BT, bt::Ready, execution::traversal::{search_next, search_start}, nodes_bin::{node::Node, node_status::Status}};
+use crate::{BT, bt::Ready, execution::traversal::{TraversalState, search_next_with, search_start_with}, nodes_bin::{node_arena::NodeIndex, node_status::Status}};
 
 
-pub(crate) type BehaviorTreeMap = HashMap<(Node, Status), Option<Node>>;
+pub(crate) type BehaviorTreeMap = HashMap<(NodeIndex, Status), Option<NodeIndex>>;
 
+// Transitions that pass a stateful composite, their next node is only known at runtime
+pub(crate) type RuntimeTransitions = HashSet<(NodeIndex, Status)>;
+
+#[cfg(test)]
 pub(crate) fn convert_bt(bt: &BT<Ready>) -> BehaviorTreeMap {
+    convert_bt_with_runtime(bt).0
+}
+
+// The runtime transitions are stored in the map with the result of a fresh state,
+// and all leaves below the stateful composites they pass are explored as well.
+// A checked-once condition gets the same transitions as a monitored one: both are looked up when the
+// condition finishes, and a monitored one also when it flips later. Whether it is monitored is read from the arena
+pub(crate) fn convert_bt_with_runtime(bt: &BT<Ready>) -> (BehaviorTreeMap, RuntimeTransitions) {
+    explore(bt, |_, _| true)
+}
+
+// Explores the transitions from the start node, only following the results a leaf can return
+pub(crate) fn explore<F>(bt: &BT<Ready>, returns: F) -> (BehaviorTreeMap, RuntimeTransitions)
+where
+    F: Fn(NodeIndex, Status) -> bool,
+{
     let mut map = HashMap::new();
+    let mut runtime = HashSet::new();
     let mut visited = HashSet::new();
     let mut queue = VecDeque::new();
 
-    let start_vec = search_start(&bt);
-    let Some(start) = start_vec.last().cloned() else { return map };
+    // Every transition is searched from a fresh state, the draws of the random composites don't matter here
+    let mut state = TraversalState::new(Some(0));
+    let Some(start) = search_start_with(&bt.arena, &mut state) else { return (map, runtime) };
 
-    queue.push_back(start_vec.clone());
-    visited.insert(start.clone());
+    queue.push_back(start);
+    visited.insert(start);
 
     while let Some(current) = queue.pop_front() {
-        let Some(current_node) = current.last() else { continue };
-
-        for &status in &[Status::Success, Status::Failure] {
-            let next_vec = search_next(current.clone(), &status);
-            let next_node = next_vec.last().cloned();
+        for status in [Status::Success, Status::Failure].into_iter().filter(|status| returns(current, *status)) {
+            state.reset();
+            let next_node = search_next_with(&bt.arena, &mut state, current, &status);
 
             // Insert into map
-            map.insert((current_node.clone(), status), next_node.clone());
+            map.insert((current, status), next_node);
 
-            // Enqueue trace if not node not already visited
-            if let Some(next) = next_node {
-                if visited.insert(next.clone()) {
-                    queue.push_back(next_vec);
+            if !state.passed.is_empty() {
+                runtime.insert((current, status));
+            }
+
+            // Enqueue node if not already visited
+            let stateful_leaves = state.passed.iter().flat_map(|node| leaves(bt, *node));
+            for next in next_node.into_iter().chain(stateful_leaves) {
+                if visited.insert(next) {
+                    queue.push_back(next);
                 }
             }
         }
     }
 
-    map
-}
\ No newline at end of file
+    (map, runtime)
+}
+
+// All nodes with a process below the given node, including itself
+pub(crate) fn leaves(bt: &BT<Ready>, node: NodeIndex) -> Vec<NodeIndex> {
+    let own = bt.arena.has_process(node).then_some(node);
+    own.into_iter().chain(bt.arena.children(node).iter().flat_map(|child| leaves(bt, *child))).collect()
+}
@@ -1,2 +1,4 @@
+pub(crate) mod analyzer;
 pub(crate) mod converter;
+pub(crate) mod optimizer;
 pub(super) mod static_engine;
\ No newline at end of file
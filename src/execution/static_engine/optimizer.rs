@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+
+use crate::{BT, bt::Ready, nodes_bin::{node_arena::{NodeArena, NodeIndex, NodeKind}, node_info::NodeMeta, node_message::ChildMessage, node_status::Status}};
+
+// Rewrites the tree into one that returns the same with fewer nodes: nested sequences and fallbacks are flattened,
+// Success and Failure leaves are folded into their parents and plain wrappers of a single child are removed.
+// Named nodes are kept. Random and weighted composites keep their children, fewer children would change their draws
+pub(crate) fn optimize(mut bt: BT<Ready>) -> BT<Ready> {
+    let arena = simplify(&bt, bt.arena.root());
+
+    // The processes of the removed leaves are not needed anymore
+    let kept: HashSet<_> = arena.indices().filter_map(|idx| arena.get_id(idx).cloned()).collect();
+    let removed = bt.map.keys().filter(|id| !kept.contains(*id)).cloned().collect::<Vec<_>>();
+    for id in removed {
+        if let Some(mut handle) = bt.map.remove(&id) {
+            tokio::spawn(async move { handle.send(ChildMessage::Kill).await });
+        }
+    }
+
+    bt.arena = arena;
+    bt
+}
+
+fn simplify(bt: &BT<Ready>, idx: NodeIndex) -> NodeArena {
+    let arena = &bt.arena;
+    let kind = arena.kind(idx);
+    let children = arena.children(idx).iter().map(|child| simplify(bt, *child));
+    let neutral = match kind {
+        NodeKind::Sequence | NodeKind::SequenceWithMemory => Status::Success,
+        NodeKind::Fallback | NodeKind::FallbackWithMemory => Status::Failure,
+        _ => return arena.rebuild(idx, children.collect()),
+    };
+
+    // Unnamed children of the same kind are replaced by their own children
+    let plain = matches!(kind, NodeKind::Sequence | NodeKind::Fallback);
+    let children = children.flat_map(|child| {
+        let root = child.root();
+        match plain && child.kind(root) == kind && child.get(root).meta == NodeMeta::default() {
+            true => child.children(root).iter().map(|grandchild| child.subtree(*grandchild)).collect(),
+            false => vec![child],
+        }
+    });
+
+    // A neutral leaf is skipped, and the children after a deciding leaf never run
+    let mut kept = vec![];
+    let mut skipped = None;
+    for child in children {
+        match constant(bt, &child) {
+            Some(status) if status == neutral => {
+                skipped.get_or_insert(child);
+            }
+            Some(_) => {
+                kept.push(child);
+                break;
+            }
+            None => kept.push(child),
+        }
+    }
+    if kept.is_empty() {
+        kept.extend(skipped); // Only neutral leaves, so it always returns that
+    }
+
+    // A plain sequence or fallback returns the result of its only child
+    if plain && kept.len() == 1 && arena.get(idx).meta == NodeMeta::default() {
+        let mut child = kept.remove(0);
+        child.set_weight(arena.get(idx).weight);
+        return child;
+    }
+    arena.rebuild(idx, kept)
+}
+
+// Result of the subtree if it is an unnamed leaf that always returns the same
+fn constant(bt: &BT<Ready>, subtree: &NodeArena) -> Option<Status> {
+    if subtree.get(subtree.root()).meta != NodeMeta::default() {
+        return None; // Named leaves are kept, like named composites
+    }
+    match subtree.kind(subtree.root()) {
+        NodeKind::Action(id) => bt.map.get(id).and_then(|handle| handle.constant()).map(|result| result.into()),
+        _ => None,
+    }
+}
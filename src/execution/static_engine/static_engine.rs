@@ -1,114 +1,85 @@
-use futures::future::select_all;
 use futures::FutureExt;
-use log::{error, trace, warn};
+use log::{error, warn};
 
 use crate::bt::Ready;
-use crate::execution::engine_factory::Engine;
+use crate::execution::engine_factory::{Engine, EventEngine};
 use crate::execution::process_comms::{FutureVec, ProcessComms};
+use crate::execution::tree_events::TreeEvents;
+use crate::nodes_bin::node_arena::{NodeArena, NodeIndex};
 use crate::nodes_bin::process_handle::ProcessHandle;
-use crate::{BT, execution::{traversal::search_start, static_engine::converter::{BehaviorTreeMap, convert_bt}}, nodes_bin::{node::Node, node_message::{ChildMessage, FutResult, ParentMessage}, node_status::Status}};
-
-
+use crate::{BT, execution::{traversal::{TraversalState, search_next_with, search_start_with}, static_engine::converter::{BehaviorTreeMap, RuntimeTransitions, convert_bt_with_runtime}}, nodes_bin::{node_message::{ChildMessage, FutResult, ParentMessage}, node_status::Status}};
 
 pub(crate) struct StaticEngine {
-    current_node: Node,
+    arena: NodeArena,
+    current_node: NodeIndex,
     map: BehaviorTreeMap,
-    active_conditions: Vec<Node>,
+    runtime: RuntimeTransitions, // Through memory and random composites, searched with the seeded state like the DynamicEngine
+    state: TraversalState,
+    active_conditions: Vec<NodeIndex>,
     comms: ProcessComms,
+    events: TreeEvents,
 }
 
 impl StaticEngine {
     pub(crate) fn new(tree: &BT<Ready>) -> StaticEngine {
-        let current_node = search_start(tree)
-            .last()
-            .cloned()
-            .unwrap_or(Node::Sequence(vec![])); // Empty sequence as default
+        let mut state = TraversalState::new(tree.seed);
+        let current_node = search_start_with(&tree.arena, &mut state)
+            .unwrap_or(tree.arena.root()); // Empty selector as default
 
-        let map = convert_bt(tree);
+        let (map, runtime) = convert_bt_with_runtime(tree);
 
         Self {
+            arena: tree.arena.clone(),
             current_node,
             map,
+            runtime,
+            state,
             active_conditions: vec![],
-            comms: ProcessComms::new(tree.map.clone()),
+            comms: ProcessComms::new(&tree.arena, &tree.map),
+            events: TreeEvents::new(tree),
         }
     }
 
-    async fn handle_current_node_finished(&mut self, status: bool) -> Option<bool>{
-        let Some(next_node) = self.lookup_next(self.current_node.clone(), status) else {
-            // The tree is finished
-            self.kill_running().await;
-            return Some(status);
-        };
-
-        // If the previous node was a condition, keep monitoring it
-        if let Node::Condition(_) = self.current_node {
-            self.active_conditions.push(self.current_node.clone());
+    // Only the transitions through stateful nodes are searched, the rest is precomputed.
+    // Err with the result of the tree if it is finished, decorators can change it on the way up
+    fn lookup_next(&mut self, node: NodeIndex, status: bool) -> Result<NodeIndex, bool> {
+        let key = (node, status.into());
+        if self.runtime.contains(&key) {
+            return search_next_with(&self.arena, &mut self.state, node, &key.1)
+                .ok_or_else(|| self.state.outcome.map_or(status, |outcome| outcome.is_succes()));
         }
-
-        self.current_node = next_node;
-        None
-    }
-
-    async fn handle_condition_trigger(&mut self, node: Node, status: bool, index: usize) -> Option<bool> {
-        self.stop_conditions_after_idx(index).await;
-
-        let Some(next_node) = self.lookup_next(node, status) else {
-            // The tree is finished
-            self.kill_running().await;
-            return Some(status);
-        };
-
-        self.current_node = next_node;
-        None
-    }
-
-    fn lookup_next(&self, node: Node, status: bool) -> Option<Node>{
-        self.map.get(&(node, status.into())).cloned().flatten()
+        self.map.get(&key).copied().flatten().ok_or(status)
     }
 
     async fn stop_conditions_after_idx(&mut self, idx: usize) {
         for condition in self.active_conditions.split_off(idx + 1) {
-            let Some(id) = condition.get_id() else {
-                panic!("Unexpected node type");
-            };
-            let _ = self.comms.send(id, ChildMessage::Stop).await;
+            let _ = self.comms.send(condition, ChildMessage::Stop).await;
+            self.events.emit(condition, Status::Idle);
         }
     }
 
-    async fn start_current_node(&mut self) {
-        let Some(id) = self.current_node.get_id() else {
-            panic!("Unexpected node type");
-        };
-        if let Err(err) = self.comms.send(id, ChildMessage::Start).await {
-            panic!("{:?} gave error {:?}", self.current_node, err);
-        }
-    }
-
-    fn build_listener_futures<'a>(&'a mut self) -> FutureVec<'a>{
-        let mut futures = vec![];
-
-        // Futures for all active conditions
-        for cond in self.active_conditions.clone().iter_mut() {
-            let Some(id) = cond.get_id() else {
-                panic!("Unexpected node type");
-            };
-            let handle = self.comms.get_handle(id).expect("No process found!");
-            futures.push(Self::run_condition(cond.clone(), handle.clone()).boxed());
+    // A loop that restarted its child starts the conditions below it over
+    async fn stop_restarted_conditions(&mut self) {
+        for looped in std::mem::take(&mut self.state.restarted) {
+            let (stopped, kept): (Vec<_>, Vec<_>) = self.active_conditions.iter()
+                .partition(|&&condition| self.arena.is_below(condition, looped));
+            self.active_conditions = kept;
+            for condition in stopped {
+                let _ = self.comms.send(condition, ChildMessage::Stop).await;
+                self.events.emit(condition, Status::Idle);
+            }
         }
-
-        // Future for current action
-        futures.push(self.run_current_node().boxed());
-        futures
     }
 
-    async fn run_condition(node: Node, mut handle: ProcessHandle) -> FutResult{
+    async fn run_condition(node: NodeIndex, mut handle: ProcessHandle, events: TreeEvents) -> FutResult{
         loop {
             match handle.listen().await {
                 Ok(msg) => {
                     match msg {
-                        ParentMessage::Status(Status::Success) => return FutResult::Condition(node.clone(), true),
-                        ParentMessage::Status(Status::Failure) => return FutResult::Condition(node.clone(), false),
+                        ParentMessage::Status(Status::Success) => return FutResult::Condition(node, true),
+                        ParentMessage::Status(Status::Failure) => return FutResult::Condition(node, false),
+                        ParentMessage::Branch(branch) => return FutResult::Branch(node, branch),
+                        ParentMessage::Stale(result) => events.stale(node, result), // A flip follows if the result changed
                         _ => {} // Other messages should not be possible
                     }
                 },
@@ -120,27 +91,30 @@ impl StaticEngine {
     }
 
     async fn run_current_node(&mut self) -> FutResult {
-        let node = self.current_node.clone();
-        let Some(id) = node.get_id() else {
-            panic!("Unexpected node type");
-        };
-        let handle = self.comms.get_handle(id).expect("No process found!");
+        let node = self.current_node;
+        let handle = self.comms.get_handle(node).expect("No process found!");
         loop {
             match handle.listen().await {
+                Ok(ParentMessage::Progress(progress)) => self.events.progress(node, progress),
+                Ok(ParentMessage::Stale(result)) => self.events.stale(node, result),
+                Ok(ParentMessage::Branch(branch)) => {
+                    self.state.select_branch(node, branch);
+                    return FutResult::CurrentNode(true);
+                }
                 Ok(msg) => {
-                    if let Some(res) = Self::process_parent_message(node.clone(), msg) {
+                    if let Some(res) = Self::process_parent_message(node, msg) {
                         return FutResult::CurrentNode(res)
                     }
                 },
                 Err(err) => {
-                    warn!("{:?} has error {:?}", self.current_node, err);
+                    warn!("{:?} ({}) has error {:?}", node, self.events.name(node), err);
                     return FutResult::CurrentNode(false);
                 },
             }
         }
     }
 
-    fn process_parent_message(node: Node, msg: ParentMessage) -> Option<bool>{
+    fn process_parent_message(node: NodeIndex, msg: ParentMessage) -> Option<bool>{
         match msg {
             ParentMessage::Status(status) => match status {
                     Status::Success => {
@@ -151,6 +125,7 @@ impl StaticEngine {
                     },
                     _ => None
                 },
+            ParentMessage::Progress(_) | ParentMessage::Branch(_) | ParentMessage::Stale(_) => None,
             ParentMessage::Poison(err) => {
                 warn!("{:?} is poisoned with error: {:?}", node, err);
                 Some(false)
@@ -162,48 +137,143 @@ impl StaticEngine {
         }
     }
 
-    async fn kill_running(&mut self) {
-        let Some(id) = self.current_node.get_id() else {
-            panic!("Unexpected node type");
-        };
-        let _ = self.comms.send(id, ChildMessage::Kill).await;
+    // The tree is finished by the result of the given node. The nodes still running or monitored
+    // next to it are reported Idle, like stopped nodes
+    async fn kill_running(&mut self, finished_by: NodeIndex) {
+        let mut killed = vec![self.current_node];
+        killed.extend(self.active_conditions.clone());
+        for node in killed {
+            let _ = self.comms.send(node, ChildMessage::Kill).await;
+            if node != finished_by {
+                self.events.emit(node, Status::Idle);
+            }
+        }
+    }
 
-        for con in self.active_conditions.clone() {
-            let Some(id) = con.get_id() else {
-                panic!("Unexpected node type");
-            };
-            let _ = self.comms.send(id, ChildMessage::Kill).await;
+    // Stops the running node when a trigger moved the tree away from it, see DynamicEngine::stop_preempted()
+    async fn stop_preempted(&mut self, next_node: NodeIndex) {
+        if next_node == self.current_node {
+            return;
+        }
+        self.state.leave(&self.arena, self.current_node, next_node);
+        if !self.arena.is_watcher(self.current_node) {
+            let _ = self.comms.send(self.current_node, ChildMessage::Stop).await;
+            self.events.emit(self.current_node, Status::Idle);
         }
     }
-}
 
-impl Engine for StaticEngine {
-    async fn run(&mut self) -> bool {
-        loop {
-            if self.current_node == Node::Sequence(vec![]) {
-                warn!("Not Running Empty Selector");
-                return false;
-            }
+    // Continues from a triggered condition or switch, stopping the conditions it no longer runs under
+    async fn retarget(&mut self, node: NodeIndex, status: bool, index: usize) -> Result<NodeIndex, bool> {
+        if index >= self.active_conditions.len() {
+            error!("Given index of condition is greater than amount of running conditions!");
+            return Err(false);
+        }
 
-            self.start_current_node().await;
+        self.events.emit(node, status.into());
+        self.stop_conditions_after_idx(index).await;
 
-            let futures: FutureVec = self.build_listener_futures();
-            if futures.len() == 0 {
-                error!("Zero listener futures in engine!"); // This should not happen
-                return false;
+        let next_node = match self.lookup_next(node, status) {
+            Ok(next_node) => next_node,
+            Err(result) => {
+                // The tree is finished
+                self.kill_running(node).await;
+                return Err(result);
             }
-            
-            let (result, index, _) = select_all(futures).await;
-            trace!("Future with index {:?} returned: {:?}", index,result);
-
-            if let Some(res) = match result {
-                // Current node finished
-                FutResult::CurrentNode(res) => self.handle_current_node_finished(res).await,
-                // Previous condition switched
-                FutResult::Condition(node, status) => self.handle_condition_trigger(node, status, index).await,
-            } {
-                return res;
+        };
+
+        self.stop_restarted_conditions().await;
+        Ok(next_node)
+    }
+}
+
+impl EventEngine for StaticEngine {
+    fn arena(&self) -> &NodeArena {
+        &self.arena
+    }
+
+    fn current_node(&self) -> NodeIndex {
+        self.current_node
+    }
+
+    fn active_conditions(&self) -> &[NodeIndex] {
+        &self.active_conditions
+    }
+
+    async fn handle_current_node_finished(&mut self, status: bool) -> Option<bool>{
+        self.events.emit(self.current_node, status.into());
+
+        let next_node = match self.lookup_next(self.current_node, status) {
+            Ok(next_node) => next_node,
+            Err(result) => {
+                // The tree is finished
+                self.kill_running(self.current_node).await;
+                return Some(result);
             }
+        };
+
+        // If the previous node was a condition, keep monitoring it
+        if self.arena.is_reactive(self.current_node) {
+            self.active_conditions.push(self.current_node);
+        } else if self.arena.is_watcher(self.current_node) {
+            let _ = self.comms.send(self.current_node, ChildMessage::Stop).await; // Checked once
         }
+        self.stop_restarted_conditions().await;
+
+        self.current_node = next_node;
+        None
     }
-}
\ No newline at end of file
+
+    async fn handle_condition_trigger(&mut self, node: NodeIndex, status: bool, index: usize) -> Option<bool> {
+        let next_node = match self.retarget(node, status, index).await {
+            Ok(next_node) => next_node,
+            Err(result) => return Some(result),
+        };
+
+        self.stop_preempted(next_node).await;
+        self.current_node = next_node;
+        None
+    }
+
+    async fn handle_branch_change(&mut self, node: NodeIndex, branch: usize, index: usize) -> Option<bool> {
+        self.state.select_branch(node, branch);
+        let next_node = match self.retarget(node, true, index).await {
+            Ok(next_node) => next_node,
+            Err(result) => return Some(result),
+        };
+
+        // Stop the node running in the old branch, the new branch starts from its first node
+        self.stop_preempted(next_node).await;
+
+        self.current_node = next_node;
+        None
+    }
+
+    async fn start_current_node(&mut self) {
+        if !self.arena.is_watcher(self.current_node) {
+            self.events.emit(self.current_node, Status::Running);
+        }
+        if let Err(err) = self.comms.send(self.current_node, ChildMessage::Start).await {
+            panic!("{:?} gave error {:?}", self.current_node, err);
+        }
+    }
+
+    fn build_listener_futures<'a>(&'a mut self) -> FutureVec<'a>{
+        let mut futures = vec![];
+
+        // Futures for all active conditions
+        for cond in self.active_conditions.clone() {
+            let handle = self.comms.get_handle(cond).expect("No process found!");
+            futures.push(Self::run_condition(cond, handle.clone(), self.events.clone()).boxed());
+        }
+
+        // Future for current action
+        futures.push(self.run_current_node().boxed());
+        futures
+    }
+}
+
+impl Engine for StaticEngine {
+    async fn run(&mut self) -> bool {
+        self.run_events().await
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use tokio::sync::broadcast::{Receiver, error::TryRecvError};
+use tokio::time::{Duration, Instant};
+
+use crate::{execution::tree_events::TreeEvent, nodes_bin::{node_arena::NodeIndex, node_info::NodeInfo, node_status::Status}};
+
+// Counts of a node over the runs, from the tree events
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeStatistics {
+    pub node: NodeInfo,
+    pub starts: u32,
+    pub successes: u32,
+    pub failures: u32,
+    pub halts: u32, // Stopped while it was running
+    pub running_time: Duration,
+    #[serde(skip)]
+    started_at: Option<Instant>,
+}
+
+// Collects statistics of the nodes that report tree events, read them with get() during or after the run
+pub struct Statistics {
+    rx: Receiver<TreeEvent>,
+    nodes: BTreeMap<NodeIndex, NodeStatistics>,
+}
+
+impl Statistics {
+    pub(crate) fn new(rx: Receiver<TreeEvent>) -> Statistics {
+        Self { rx, nodes: BTreeMap::new() }
+    }
+
+    // Statistics of the nodes that reported events so far, in tree order
+    pub fn get(&mut self) -> Vec<NodeStatistics> {
+        loop {
+            match self.rx.try_recv() {
+                Ok(event) => self.record(event),
+                Err(TryRecvError::Lagged(n)) => log::warn!("Statistics missed {n} tree events, read them more often"),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+        self.nodes.values().cloned().collect()
+    }
+
+    fn record(&mut self, event: TreeEvent) {
+        // Progress reports and stale values come on top of the status changes
+        if event.progress.is_some() || event.stale {
+            return;
+        }
+        let stats = self.nodes.entry(event.index).or_insert_with(|| NodeStatistics {
+            node: event.node.clone(),
+            starts: 0,
+            successes: 0,
+            failures: 0,
+            halts: 0,
+            running_time: Duration::ZERO,
+            started_at: None,
+        });
+
+        if event.status == Status::Running {
+            if stats.started_at.is_none() {
+                stats.starts += 1;
+                stats.started_at = Some(event.time);
+            }
+            return;
+        }
+
+        let was_running = match stats.started_at.take() {
+            Some(since) => {
+                stats.running_time += event.time.duration_since(since);
+                true
+            }
+            None => false,
+        };
+        match event.status {
+            Status::Success => stats.successes += 1,
+            Status::Failure => stats.failures += 1,
+            Status::Idle if was_running => stats.halts += 1,
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1 @@
+pub(crate) mod tick_engine;
\ No newline at end of file
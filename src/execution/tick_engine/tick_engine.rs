@@ -0,0 +1,264 @@
+use std::collections::{HashMap, HashSet};
+
+use log::warn;
+use tokio::time::{Duration, MissedTickBehavior, interval};
+
+use crate::bt::Ready;
+use crate::execution::engine_factory::Engine;
+use crate::execution::process_comms::ProcessComms;
+use crate::execution::tree_events::TreeEvents;
+use crate::execution::traversal::{TraversalState, search_next_with, search_start_with};
+use crate::nodes_bin::node_arena::{NodeArena, NodeIndex};
+use crate::{BT, nodes_bin::{node_message::{ChildMessage, ParentMessage}, node_status::Status}};
+
+// Polling engine: every tick walks the tree from the root, re-evaluating all conditions on the way.
+// Actions that finished earlier in the run are not executed again, unless a condition before
+// them changed its result. Memory composites skip their completed children on the walk.
+pub(crate) struct TickEngine {
+    arena: NodeArena,
+    period: Duration,
+    results: HashMap<NodeIndex, Status>, // Last result of each visited leaf in the current run
+    state: TraversalState, // Memory of the stateful composites in the current run
+    running: Option<NodeIndex>, // Action left Running by a previous tick
+    stale: HashSet<NodeIndex>, // Conditions whose input is stale
+    comms: ProcessComms,
+    events: TreeEvents,
+}
+
+impl TickEngine {
+    pub(crate) fn new(tree: &BT<Ready>, period: Duration) -> TickEngine {
+        Self {
+            arena: tree.arena.clone(),
+            period,
+            results: HashMap::new(),
+            state: TraversalState::new(tree.seed),
+            running: None,
+            stale: HashSet::new(),
+            comms: ProcessComms::new(&tree.arena, &tree.map),
+            events: TreeEvents::new(tree),
+        }
+    }
+
+    // Returns Running while the tree is busy, a finished run restarts on the next tick
+    pub(crate) async fn tick(&mut self) -> Status {
+        let Some(mut node) = search_start_with(&self.arena, &mut self.state) else {
+            if let Some(result) = self.state.outcome {
+                // Finished by a decorator without running anything
+                self.state.clear();
+                return result;
+            }
+            warn!("Not Running Empty Selector");
+            return Status::Failure;
+        };
+
+        let mut changed = false;
+        loop {
+            let status = if self.arena.is_watcher(node) {
+                match self.results.get(&node) {
+                    Some(&status) if !self.arena.is_reactive(node) => status, // Checked once earlier in this run
+                    _ => {
+                        let branch = self.state.branch(node);
+                        let status = self.evaluate_condition(node).await;
+                        if self.results.insert(node, status) != Some(status) || self.state.branch(node) != branch {
+                            self.events.emit(node, status);
+                            changed = true;
+                        }
+                        status
+                    }
+                }
+            } else {
+                self.tick_action(node, changed).await
+            };
+
+            if status.is_running() {
+                return status;
+            }
+
+            let Some(next_node) = search_next_with(&self.arena, &mut self.state, node, &status) else {
+                // The tree is finished, decorators can change the result on the way up
+                let result = self.state.outcome.unwrap_or(status);
+                self.halt_running().await;
+                self.stop_watchers().await;
+                self.results.clear();
+                self.state.clear();
+                return result;
+            };
+            // A loop that restarted its child runs the nodes below it again
+            for looped in std::mem::take(&mut self.state.restarted) {
+                self.results.retain(|&leaf, _| !self.arena.is_below(leaf, looped));
+            }
+            node = next_node;
+        }
+    }
+
+    async fn tick_action(&mut self, node: NodeIndex, changed: bool) -> Status {
+        match self.results.get(&node) {
+            Some(Status::Running) => {
+                let status = self.poll_action(node);
+                if !status.is_running() {
+                    self.events.emit(node, status);
+                    self.running = None;
+                }
+                self.results.insert(node, status);
+                status
+            }
+            Some(&status) if !changed => status, // Finished earlier in this run
+            _ => {
+                self.start_action(node).await;
+                self.results.insert(node, Status::Running);
+                Status::Running
+            }
+        }
+    }
+
+    // Switches are evaluated like conditions, they succeed with the selected branch
+    async fn evaluate_condition(&mut self, node: NodeIndex) -> Status {
+        let handle = self.comms.get_handle(node).expect("No process found!");
+        handle.clear(); // Drop updates the condition sent since the last tick
+        if let Err(err) = handle.send(ChildMessage::Start).await {
+            panic!("{:?} gave error {:?}", node, err);
+        }
+
+        let mut stale = false;
+        loop {
+            match handle.listen().await {
+                Ok(ParentMessage::Status(status @ (Status::Success | Status::Failure))) => {
+                    if !stale {
+                        self.stale.remove(&node);
+                    }
+                    return status;
+                }
+                Ok(ParentMessage::Status(_)) => {}
+                Ok(ParentMessage::Stale(result)) => {
+                    // Sent on every start while the input is stale, reported once
+                    stale = true;
+                    if self.stale.insert(node) {
+                        self.events.stale(node, result);
+                    }
+                }
+                Ok(ParentMessage::Branch(branch)) => {
+                    self.state.select_branch(node, branch);
+                    return Status::Success;
+                }
+                Ok(msg) => {
+                    warn!("{:?} ({}) returned {:?}", node, self.events.name(node), msg);
+                    return Status::Failure;
+                }
+                Err(err) => {
+                    warn!("{:?} ({}) has error {:?}", node, self.events.name(node), err);
+                    return Status::Failure;
+                }
+            }
+        }
+    }
+
+    async fn start_action(&mut self, node: NodeIndex) {
+        if let Some(running) = self.running.filter(|running| *running != node) {
+            self.state.leave(&self.arena, running, node);
+            self.halt_running().await;
+        }
+        self.events.emit(node, Status::Running);
+
+        let handle = self.comms.get_handle(node).expect("No process found!");
+        handle.clear();
+        if let Err(err) = handle.send(ChildMessage::Start).await {
+            panic!("{:?} gave error {:?}", node, err);
+        }
+        self.running = Some(node);
+    }
+
+    fn poll_action(&mut self, node: NodeIndex) -> Status {
+        let handle = self.comms.get_handle(node).expect("No process found!");
+        while let Some(msg) = handle.try_listen() {
+            match msg {
+                ParentMessage::Status(status @ (Status::Success | Status::Failure)) => return status,
+                ParentMessage::Status(_) | ParentMessage::Branch(_) | ParentMessage::Stale(_) => {}
+                ParentMessage::Progress(progress) => self.events.progress(node, progress),
+                ParentMessage::Poison(err) => {
+                    warn!("{:?} ({}) is poisoned with error: {:?}", node, self.events.name(node), err);
+                    return Status::Failure;
+                }
+                ParentMessage::Killed => {
+                    warn!("{:?} ({}) has been killed", node, self.events.name(node));
+                    return Status::Failure;
+                }
+            }
+        }
+        Status::Running
+    }
+
+    async fn halt_running(&mut self) {
+        if let Some(node) = self.running.take() {
+            self.results.remove(&node);
+            let _ = self.comms.send(node, ChildMessage::Stop).await;
+            self.events.emit(node, Status::Idle);
+        }
+    }
+
+    // Conditions and switches evaluated in this run, a switch forgets its selection for the next run
+    async fn stop_watchers(&mut self) {
+        let watchers: Vec<_> = self.results.keys().copied().filter(|node| self.arena.is_watcher(*node)).collect();
+        for node in watchers {
+            let _ = self.comms.send(node, ChildMessage::Stop).await;
+        }
+    }
+
+    async fn reset(&mut self) {
+        self.halt_running().await;
+        self.stop_watchers().await;
+        self.results.clear();
+        self.state.reset();
+    }
+
+    async fn kill_all(&mut self) {
+        for node in self.arena.indices().filter(|node| self.arena.has_process(*node)) {
+            let _ = self.comms.send(node, ChildMessage::Kill).await;
+        }
+    }
+}
+
+impl Engine for TickEngine {
+    async fn run(&mut self) -> bool {
+        let mut interval = interval(self.period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+            let res: Option<bool> = self.tick().await.into();
+            if let Some(res) = res {
+                self.kill_all().await;
+                return res;
+            }
+        }
+    }
+}
+
+// Drives a tree with manual ticks, e.g. from an existing control loop
+pub struct Ticker {
+    engine: TickEngine,
+}
+
+impl Ticker {
+    pub(crate) fn new(tree: &BT<Ready>) -> Ticker {
+        Self {
+            engine: TickEngine::new(tree, Duration::ZERO), // The period is only used by run()
+        }
+    }
+
+    pub async fn tick(&mut self) -> Status {
+        self.engine.tick().await
+    }
+
+    // Halts the running action and forgets everything of the earlier runs,
+    // including the timers and results of the Cooldown, RateLimit and RunOnce decorators
+    pub async fn reset(&mut self) {
+        self.engine.reset().await
+    }
+}
+
+// The processes of the nodes would otherwise keep running after the ticker is gone
+impl Drop for Ticker {
+    fn drop(&mut self) {
+        self.engine.comms.kill_all_now();
+    }
+}
@@ -1,55 +1,337 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
 use log::warn;
+use rand::{SeedableRng, distr::{Distribution, weighted::WeightedIndex}, rngs::StdRng, seq::SliceRandom};
+use tokio::time::Instant;
 
-use crate::{BT, bt::Ready, nodes_bin::{node::Node, node_status::Status}};
+use crate::{nodes_bin::{node_arena::{NodeArena, NodeIndex, NodeKind}, node_status::Status}};
+#[cfg(test)]
+use crate::{BT, bt::Ready};
 
-pub(crate) fn search_start(tree: &BT<Ready>) -> Vec<Node> {
-    search_down(tree.root.clone(), vec![])
+// Runtime state of the composites whose next child depends on earlier results in the run.
+// Memory composites remember their completed children, and random composites the order they drew
+// when they were entered. Both are kept until the composite finishes itself or the run ends,
+// so a condition trigger below them resumes in the same order.
+// Decorators decide each time they are entered whether they run their child, except while it runs.
+// Their timers and RunOnce results outlive the run, until reset() is called.
+#[derive(Debug)]
+pub(crate) struct TraversalState {
+    completed: HashSet<NodeIndex>, // Children of memory composites that completed
+    orders: HashMap<NodeIndex, Vec<NodeIndex>>, // Drawn order of the children of random composites
+    branches: HashMap<NodeIndex, usize>, // Last branch reported by each switch
+    entered: HashSet<NodeIndex>, // Decorators that started their child and did not finish yet
+    once: HashMap<NodeIndex, Status>, // Result of each RunOnce that ran its child
+    finished_at: HashMap<NodeIndex, Instant>, // Last time the child of each Cooldown finished
+    starts: HashMap<NodeIndex, VecDeque<Instant>>, // Starts of the child of each RateLimit within its period
+    looping: HashSet<NodeIndex>, // Loops that restart their child in the current search
+    rng: StdRng,
+    pub(crate) passed: Vec<NodeIndex>, // Stateful nodes passed by the last search
+    pub(crate) restarted: Vec<NodeIndex>, // Loops that restarted their child in the last search
+    pub(crate) outcome: Option<Status>, // Result of the root, if the last search finished the tree
 }
 
-fn search_down(node: Node, mut trace: Vec<Node>) -> Vec<Node> {
-    match &node {
-        Node::Action(_) | Node::Condition(_) => {
-            trace.push(node.clone());
-            trace
-        },
-        Node::Fallback(children) | Node::Sequence(children) => {
-            if let Some(child) = children.first() {
-                trace.push(node.clone());
-                search_down( child.clone(), trace)
-            } else {
-                warn!("Found empty selector!");
-                vec![]
+impl Default for TraversalState {
+    fn default() -> Self {
+        TraversalState::new(None)
+    }
+}
+
+impl TraversalState {
+    // Without a seed the random composites draw from the OS
+    pub(crate) fn new(seed: Option<u64>) -> TraversalState {
+        Self {
+            completed: HashSet::new(),
+            orders: HashMap::new(),
+            branches: HashMap::new(),
+            entered: HashSet::new(),
+            once: HashMap::new(),
+            finished_at: HashMap::new(),
+            starts: HashMap::new(),
+            looping: HashSet::new(),
+            rng: seed.map_or_else(StdRng::from_os_rng, StdRng::seed_from_u64),
+            passed: vec![],
+            restarted: vec![],
+            outcome: None,
+        }
+    }
+
+    // Forgets the state of all composites for a new run, the rng continues
+    pub(crate) fn clear(&mut self) {
+        self.completed.clear();
+        self.orders.clear();
+        self.branches.clear();
+        self.entered.clear();
+    }
+
+    // Also forgets the timers and results of the decorators
+    pub(crate) fn reset(&mut self) {
+        self.clear();
+        self.once.clear();
+        self.finished_at.clear();
+        self.starts.clear();
+    }
+
+    pub(crate) fn select_branch(&mut self, switch: NodeIndex, branch: usize) {
+        self.branches.insert(switch, branch);
+    }
+
+    pub(crate) fn branch(&self, switch: NodeIndex) -> Option<usize> {
+        self.branches.get(&switch).copied()
+    }
+
+    fn pass(&mut self, node: NodeIndex) {
+        self.passed.push(node);
+    }
+
+    fn forget(&mut self, arena: &NodeArena, node: NodeIndex) {
+        for child in arena.children(node) {
+            self.completed.remove(child);
+        }
+        self.orders.remove(&node);
+    }
+
+    // A loop starts a new iteration: the nodes below it start over as if they were never entered
+    fn forget_below(&mut self, arena: &NodeArena, node: NodeIndex) {
+        for &child in arena.children(node) {
+            self.completed.remove(&child);
+            self.orders.remove(&child);
+            self.entered.remove(&child);
+            self.forget_below(arena, child);
+        }
+    }
+
+    // The running node was stopped because the tree moved on to the next node. The decorators above
+    // it that the next node is not below are left, entering them again decides anew
+    pub(crate) fn leave(&mut self, arena: &NodeArena, stopped: NodeIndex, next: NodeIndex) {
+        let mut current = stopped;
+        while let Some(parent) = arena.parent(current) {
+            if parent == next || arena.is_below(next, parent) {
+                return;
+            }
+            self.entered.remove(&parent);
+            current = parent;
+        }
+    }
+
+    fn first_unfinished(&self, candidates: &[NodeIndex]) -> Option<NodeIndex> {
+        candidates.iter().find(|child| !self.completed.contains(child)).copied()
+    }
+
+    // Order of a random composite, drawn when it is first needed
+    fn order(&mut self, arena: &NodeArena, node: NodeIndex) -> &[NodeIndex] {
+        if !self.orders.contains_key(&node) {
+            let order = self.draw(arena, node);
+            self.orders.insert(node, order);
+        }
+        &self.orders[&node]
+    }
+
+    fn draw(&mut self, arena: &NodeArena, node: NodeIndex) -> Vec<NodeIndex> {
+        let mut children = arena.children(node).to_vec();
+        if !matches!(arena.kind(node), NodeKind::WeightedChoice) {
+            children.shuffle(&mut self.rng);
+            return children;
+        }
+
+        let weights = children.iter().map(|child| arena.get(*child).weight);
+        match WeightedIndex::new(weights) {
+            Ok(dist) => vec![children[dist.sample(&mut self.rng)]],
+            Err(err) => {
+                warn!("Invalid weights for {:?}: {:?}, picking the first child", node, err);
+                children.truncate(1);
+                children
             }
         }
     }
+
+    // Decides whether a decorator runs its child. A condition trigger that enters it again while
+    // the child runs keeps it running, without counting another start
+    fn decide(&mut self, arena: &NodeArena, node: NodeIndex) -> Option<Status> {
+        if self.entered.contains(&node) {
+            return None;
+        }
+
+        let now = Instant::now();
+        let decision = match arena.kind(node) {
+            NodeKind::RunOnce => self.once.get(&node).copied(),
+            NodeKind::Cooldown(period) => self.finished_at.get(&node)
+                .filter(|at| now < **at + *period)
+                .map(|_| Status::Failure),
+            NodeKind::RateLimit(limit, period) => {
+                let starts = self.starts.entry(node).or_default();
+                starts.retain(|at| now < *at + *period);
+                if starts.len() < *limit as usize {
+                    starts.push_back(now);
+                    None
+                } else {
+                    Some(Status::Failure)
+                }
+            }
+            _ => None,
+        };
+        if decision.is_none() {
+            self.entered.insert(node);
+        }
+        decision
+    }
+
+    // The child of a decorator finished
+    fn settle(&mut self, arena: &NodeArena, node: NodeIndex, result: Status) {
+        self.entered.remove(&node);
+        match arena.kind(node) {
+            NodeKind::RunOnce => {
+                self.once.insert(node, result);
+            }
+            NodeKind::Cooldown(_) => {
+                self.finished_at.insert(node, Instant::now());
+            }
+            _ => (),
+        }
+    }
+
+    fn next_in_order(&mut self, arena: &NodeArena, node: NodeIndex, previous: NodeIndex) -> Option<NodeIndex> {
+        let order = self.order(arena, node);
+        let position = order.iter().position(|child| *child == previous)?;
+        order.get(position + 1).copied()
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn search_start(tree: &BT<Ready>) -> Option<NodeIndex> {
+    search_start_with(&tree.arena, &mut TraversalState::default())
 }
 
-pub(crate) fn search_next(trace: Vec<Node>, result: &Status) -> Vec<Node> {
-    search_up(trace, result, None)
+// First leaf of the tree, skipping the children the memory composites completed.
+// None if the tree is empty, or if its decorators finished it right away, see outcome
+pub(crate) fn search_start_with(arena: &NodeArena, state: &mut TraversalState) -> Option<NodeIndex> {
+    state.passed.clear();
+    state.restarted.clear();
+    state.outcome = None;
+    search_down(arena, state, arena.root())
 }
 
-fn search_up(mut trace: Vec<Node>, result: &Status, previous_node: Option<Node>) -> Vec<Node> {
-    // If trace = [], we have reached the root
-    let Some(node) = trace.pop() else {
-        return trace;
+fn search_down(arena: &NodeArena, state: &mut TraversalState, node: NodeIndex) -> Option<NodeIndex> {
+    let child = match arena.kind(node) {
+        NodeKind::Action(_) | NodeKind::Condition(_) => return Some(node),
+        NodeKind::Switch(_) => return Some(node), // Its process selects the branch first
+        NodeKind::Delay(_) => return Some(node), // Its process waits first
+        NodeKind::Fallback | NodeKind::Sequence |
+        NodeKind::KeepRunningUntilFailure => arena.children(node).first().copied(),
+        NodeKind::FallbackWithMemory | NodeKind::SequenceWithMemory => {
+            state.pass(node);
+            state.first_unfinished(arena.children(node))
+        }
+        NodeKind::RandomFallback | NodeKind::RandomSequence | NodeKind::WeightedChoice => {
+            state.pass(node);
+            state.order(arena, node).first().copied()
+        }
+        NodeKind::RunOnce | NodeKind::Cooldown(_) | NodeKind::RateLimit(..) => {
+            state.pass(node);
+            if let Some(result) = state.decide(arena, node) {
+                return search_up(arena, state, node, &result); // Finished without running the child
+            }
+            arena.children(node).first().copied()
+        }
     };
 
-    match (&node, result) {
+    if let Some(child) = child {
+        search_down(arena, state, child)
+    } else {
+        warn!("Found empty selector!");
+        None
+    }
+}
+
+// Returns the next leaf to execute, or None if the tree is finished. Treats all composites as if they were just started
+#[cfg(test)]
+pub(crate) fn search_next(arena: &NodeArena, node: NodeIndex, result: &Status) -> Option<NodeIndex> {
+    search_next_with(arena, &mut TraversalState::default(), node, result)
+}
+
+// Same as search_next, but updates the state of the stateful nodes it passes.
+// If the tree is finished, outcome holds its result
+pub(crate) fn search_next_with(arena: &NodeArena, state: &mut TraversalState, node: NodeIndex, result: &Status) -> Option<NodeIndex> {
+    state.passed.clear();
+    state.restarted.clear();
+    state.outcome = None;
+    match (arena.kind(node), result) {
+        (NodeKind::Switch(_), Status::Success) => {
+            // The switch selected a branch, continue in there. Only unknown while the static map is built
+            state.pass(node);
+            if let Some(branch) = state.branch(node).and_then(|branch| arena.children(node).get(branch)) {
+                return search_down(arena, state, *branch);
+            }
+        }
+        (NodeKind::Delay(_), Status::Success) => {
+            // The delay passed, start its child
+            if let Some(child) = arena.children(node).first() {
+                return search_down(arena, state, *child);
+            }
+        }
+        _ => (),
+    }
+    search_up(arena, state, node, result)
+}
+
+fn search_up(arena: &NodeArena, state: &mut TraversalState, previous_node: NodeIndex, result: &Status) -> Option<NodeIndex> {
+    // If there is no parent, we have reached the root
+    let Some(node) = arena.parent(previous_node) else {
+        state.outcome = Some(*result);
+        return None;
+    };
+
+    match (arena.kind(node), result) {
         // If previous node was not the last child of a selector, select next child and search down
-        (Node::Fallback(children), Status::Failure) | 
-        (Node::Sequence(children), Status::Success) => {
-            if let Some(previous_node) = previous_node {
-                if let Some(next_child) = children.iter()
-                    .position(|node| *node == previous_node)
-                    .and_then(|i| children.get(i + 1))
-                    .cloned()
-                {
-                    trace.push(node.clone());
-                    return search_down(next_child, trace);
-                }
+        (NodeKind::Fallback, Status::Failure) |
+        (NodeKind::Sequence, Status::Success) => {
+            if let Some(next_child) = arena.next_sibling(previous_node) {
+                return search_down(arena, state, next_child);
             }
         },
-        (Node::Action(_) | Node::Condition(_) | Node::Sequence(_) | Node::Fallback(_),_) => ()
+        // Same, but skips the children that completed before
+        (NodeKind::FallbackWithMemory, Status::Failure) |
+        (NodeKind::SequenceWithMemory, Status::Success) => {
+            state.pass(node);
+            state.completed.insert(previous_node);
+            let position = arena.get(previous_node).position;
+            if let Some(next_child) = state.first_unfinished(&arena.children(node)[position + 1..]) {
+                return search_down(arena, state, next_child);
+            }
+            state.forget(arena, node); // Finished, forget its children
+        },
+        // Same, but in the drawn order. A WeightedChoice only has its picked child in the order
+        (NodeKind::RandomFallback, Status::Failure) |
+        (NodeKind::RandomSequence, Status::Success) => {
+            state.pass(node);
+            if let Some(next_child) = state.next_in_order(arena, node, previous_node) {
+                return search_down(arena, state, next_child);
+            }
+            state.forget(arena, node);
+        },
+        (NodeKind::FallbackWithMemory | NodeKind::SequenceWithMemory |
+         NodeKind::RandomFallback | NodeKind::RandomSequence | NodeKind::WeightedChoice, _) => {
+            state.pass(node);
+            state.forget(arena, node);
+        },
+        (NodeKind::RunOnce | NodeKind::Cooldown(_) | NodeKind::RateLimit(..), _) => {
+            state.pass(node);
+            state.settle(arena, node, *result);
+        },
+        // Start the next iteration, unless the child finished again without running any leaf
+        (NodeKind::KeepRunningUntilFailure, Status::Success) => {
+            state.pass(node);
+            if state.looping.insert(node) {
+                state.forget_below(arena, node);
+                state.restarted.push(node);
+                let next = search_down(arena, state, previous_node);
+                state.looping.remove(&node);
+                return next;
+            }
+            warn!("Loop {:?} finished its child without running it, stopping", node);
+            return search_up(arena, state, node, &Status::Failure);
+        },
+        (NodeKind::Action(_) | NodeKind::Condition(_) | NodeKind::Sequence | NodeKind::Fallback |
+         NodeKind::Switch(_) | NodeKind::Delay(_) | NodeKind::KeepRunningUntilFailure, _) => ()
     }
-    search_up(trace, result, Some(node))
-}
\ No newline at end of file
+    search_up(arena, state, node, result)
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use tokio::sync::broadcast::Sender;
+use tokio::time::Instant;
+
+use crate::{BT, bt::Ready, nodes::feedback::Progress, nodes_bin::{node_arena::NodeIndex, node_info::NodeInfo, node_status::Status}};
+
+// Status change of a node as observed by the engine, progress reported by a running action,
+// or a condition whose input went stale
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeEvent {
+    pub node: NodeInfo,
+    pub status: Status,
+    pub progress: Option<Progress>,
+    pub stale: bool, // The condition reports the status because it got no new value within its max age
+    pub time: Instant,
+    pub(crate) index: NodeIndex, // Tells apart nodes with the same info
+}
+
+#[derive(Clone)]
+pub(crate) struct TreeEvents {
+    tx: Sender<TreeEvent>,
+    nodes: Arc<Vec<NodeInfo>>, // Indexed by NodeIndex
+}
+
+impl TreeEvents {
+    pub(crate) fn new(tree: &BT<Ready>) -> TreeEvents {
+        Self {
+            tx: tree.events.clone(),
+            nodes: Arc::new(NodeInfo::collect(&tree.arena, &tree.map)),
+        }
+    }
+
+    // Name of the node for logs, as given while building the tree or else of its process
+    pub(crate) fn name(&self, node: NodeIndex) -> &str {
+        &self.nodes[node.index()].name
+    }
+
+    pub(crate) fn emit(&self, node: NodeIndex, status: Status) {
+        self.send(node, status, None, false)
+    }
+
+    pub(crate) fn progress(&self, node: NodeIndex, progress: Progress) {
+        self.send(node, Status::Running, Some(progress), false)
+    }
+
+    // Sent on top of the status change, which only follows if the stale result differs from the current one
+    pub(crate) fn stale(&self, node: NodeIndex, result: bool) {
+        self.send(node, result.into(), None, true)
+    }
+
+    fn send(&self, node: NodeIndex, status: Status, progress: Option<Progress>, stale: bool) {
+        let event = TreeEvent {
+            node: self.nodes[node.index()].clone(),
+            status,
+            progress,
+            stale,
+            time: Instant::now(),
+            index: node,
+        };
+        log::trace!("Tree event: {:?}", event);
+        let _ = self.tx.send(event); // Fails only without subscribers
+    }
+}
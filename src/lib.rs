@@ -1,15 +1,43 @@
+// Lets the bt! macro refer to behavior_tree:: from within this crate as well
+extern crate self as behavior_tree;
+
 mod bt;
 mod execution;
 mod nodes;
 mod nodes_bin;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 pub use crate::{
-    bt::BT,
+    bt::{BT, Builder},
+    execution::{
+        debug_engine::debug_engine::{Breakpoint, DebugState, Debugger, PausePoint},
+        engine_factory::Engines,
+        export::{ExportedNode, TreeExport},
+        static_engine::analyzer::Finding,
+        statistics::{NodeStatistics, Statistics},
+        tick_engine::tick_engine::Ticker,
+        tree_events::TreeEvent,
+    },
     nodes::{
-        action::{Action, Wait, Success, Failure},
-        condition::Condition,
+        action::{Action, Executor, Wait, Success, Failure},
+        blocking::{BlockingExecutor, CancelFlag},
+        condition::{Condition, ConditionOptions, Evaluator},
+        feedback::{Feedback, Progress},
+        switch::Switch,
+        utility::UtilitySelector,
+        watch::Watch,
     },
+    nodes_bin::{node_info::NodeInfo, node_status::Status},
 };
+pub use macros::{bt, bt_action, bt_condition, Executor, Evaluator};
+
+// Used by the code the macros generate
+#[doc(hidden)]
+pub use anyhow;
+#[doc(hidden)]
+pub use crate::nodes::action::{IntoResult, FutureKind, ValueKind};
 
 #[cfg(test)]
 mod tests;
@@ -5,10 +5,12 @@ use anyhow::Result;
 use tokio::sync::broadcast::{channel, Receiver, Sender};
 use tokio::time::{sleep, Duration};
 
+use crate::nodes::blocking::{BlockingAdapter, BlockingExecutor};
+use crate::nodes::feedback::Feedback;
 use crate::nodes_bin::{
     node::{NodeProcess},
     node_error::NodeError,
-    process_handle::ProcessHandle,
+    process_handle::{ProcessHandle, ProcessName},
     node_message::{ChildMessage, ParentMessage},
     node_status::Status,
 };
@@ -17,6 +19,68 @@ use crate::bt::CHANNEL_SIZE;
 pub trait Executor {
     fn get_name(&self) -> String;
     fn execute(&mut self) -> impl Future<Output = Result<bool>> + Send;
+
+    // Called once when the action is created, store the feedback to report progress from execute()
+    fn set_feedback(&mut self, _feedback: Feedback) {}
+
+    // Called when the action is stopped while it executes, after the running execute() is dropped
+    fn halt(&mut self) {}
+}
+
+// The results an executor or evaluator method may return, used by the derive macros
+#[doc(hidden)]
+pub trait IntoResult {
+    fn into_result(self) -> Result<bool>;
+}
+
+impl IntoResult for bool {
+    fn into_result(self) -> Result<bool> {
+        Ok(self)
+    }
+}
+
+impl<E: Into<anyhow::Error>> IntoResult for std::result::Result<bool, E> {
+    fn into_result(self) -> Result<bool> {
+        self.map_err(Into::into)
+    }
+}
+
+// The derive macros only see the name of the method, so the call site picks how to get its result:
+// (&output).output_kind() finds FutureKind on a future first, and ValueKind on a plain result by autoref
+#[doc(hidden)]
+pub trait FutureKind {
+    fn output_kind(&self) -> FutureOutput {
+        FutureOutput
+    }
+}
+
+impl<F: Future<Output: IntoResult>> FutureKind for F {}
+
+#[doc(hidden)]
+pub trait ValueKind {
+    fn output_kind(&self) -> ValueOutput {
+        ValueOutput
+    }
+}
+
+impl<T: IntoResult> ValueKind for &T {}
+
+#[doc(hidden)]
+pub struct FutureOutput;
+
+impl FutureOutput {
+    pub async fn resolve<F: Future<Output: IntoResult>>(self, output: F) -> Result<bool> {
+        output.await.into_result()
+    }
+}
+
+#[doc(hidden)]
+pub struct ValueOutput;
+
+impl ValueOutput {
+    pub async fn resolve<T: IntoResult>(self, output: T) -> Result<bool> {
+        output.into_result()
+    }
 }
 
 // Prevent typo errors in booleans by using explicit types
@@ -29,6 +93,62 @@ impl Action {
     {
         ActionProcess::new(inner)
     }
+
+    // The closure is called for every execution, clone captured handles into the returned future
+    pub fn from_fn<S, F, Fut>(name: S, function: F) -> ProcessHandle
+    where
+        S: Into<String>,
+        F: FnMut() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<bool>> + Send,
+    {
+        ActionProcess::new(ClosureExecutor::new(name.into(), function))
+    }
+
+    // Executes on the blocking thread pool of the current runtime
+    pub fn blocking<T>(inner: T) -> ProcessHandle
+    where
+        T: BlockingExecutor + Send + 'static,
+    {
+        ActionProcess::new(BlockingAdapter::new(inner, None))
+    }
+
+    // Executes on the blocking thread pool of the given runtime, e.g. one with a limited max_blocking_threads
+    pub fn blocking_on<T>(inner: T, pool: tokio::runtime::Handle) -> ProcessHandle
+    where
+        T: BlockingExecutor + Send + 'static,
+    {
+        ActionProcess::new(BlockingAdapter::new(inner, Some(pool)))
+    }
+}
+
+// If you pass in just a closure to Action::from_fn(), this hidden wrapper is used beneath
+pub struct ClosureExecutor<F> {
+    name: String,
+    function: F,
+}
+
+impl<F, Fut> ClosureExecutor<F>
+where
+    F: FnMut() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<bool>> + Send,
+{
+    pub fn new(name: String, function: F) -> ClosureExecutor<F> {
+        Self { name, function }
+    }
+}
+
+impl<F, Fut> Executor for ClosureExecutor<F>
+where
+    F: FnMut() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<bool>> + Send,
+{
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn execute(&mut self) -> impl Future<Output = Result<bool>> + Send {
+        (self.function)()
+    }
 }
 
 struct ActionProcess<T>
@@ -39,18 +159,20 @@ where
     rx: Option<Receiver<ChildMessage>>,
     status: Status,
     inner: T,
+    name: ProcessName,
 }
 
 impl<T> ActionProcess<T>
 where
     T: Executor + Send + Sync + 'static,
 {
-    pub fn new(inner: T) -> ProcessHandle {
+    pub fn new(mut inner: T) -> ProcessHandle {
         let (parent_tx, parent_rx) = channel(CHANNEL_SIZE);
         let (child_tx, child_rx) = channel(CHANNEL_SIZE);
 
-        let name = inner.get_name();
-        let node = Self::_new(parent_tx.clone(), child_rx, inner);
+        inner.set_feedback(Feedback::new(parent_tx.clone()));
+        let name = ProcessName::new(inner.get_name());
+        let node = Self::_new(parent_tx.clone(), child_rx, inner, name.clone());
         tokio::spawn(Self::serve(node));
 
         ProcessHandle::new(child_tx, parent_rx, name)
@@ -60,12 +182,14 @@ where
         tx: Sender<ParentMessage>,
         rx: Receiver<ChildMessage>,
         inner: T,
+        name: ProcessName,
     ) -> Self {
         Self {
             tx,
             rx: Some(rx),
             status: Status::Idle,
             inner,
+            name,
         }
     }
 
@@ -78,7 +202,7 @@ where
     async fn notify_parent(&mut self, msg: ParentMessage) -> Result<(), NodeError> {
         log::debug!(
             "Action {:?} - notify parent: {:?}",
-            self.inner.get_name(),
+            self.name,
             msg
         );
         self.tx.send(msg)?;
@@ -89,7 +213,12 @@ where
         match msg {
             ChildMessage::Kill => return Err(NodeError::KillError),
             ChildMessage::Start => self.update_status(Status::Running).await?,
-            ChildMessage::Stop => self.update_status(Status::Idle).await?, // TODO: implement .stop() method
+            ChildMessage::Stop => {
+                if self.status.is_running() {
+                    self.inner.halt();
+                }
+                self.update_status(Status::Idle).await?
+            }
         }
         Ok(())
     }
@@ -131,7 +260,7 @@ where
 impl<T: Executor + Send + Sync + 'static> NodeProcess for ActionProcess<T> {
     async fn serve(self) {
         let poison_tx = self.tx.clone();
-        let name = self.inner.get_name();
+        let name = self.name.clone();
         let res = Self::_serve(self).await;
 
         log::debug!("Action {name:?} - exited with error: {res:?}");
@@ -170,8 +299,12 @@ pub struct Wait {
 
 impl Wait {
     pub fn new(duration: Duration) -> ProcessHandle {
+        Wait::named("Waiting", duration)
+    }
+
+    pub(crate) fn named(name: impl Into<String>, duration: Duration) -> ProcessHandle {
         Action::new(Self {
-            name: "Waiting".to_string(),
+            name: name.into(),
             duration,
         })
     }
@@ -196,7 +329,7 @@ impl Success {
     pub fn new() -> ProcessHandle {
         Action::new(Self {
             name: "SUCCESS".to_string(),
-        })
+        }).with_constant(true)
     }
 }
 
@@ -218,7 +351,7 @@ impl Failure {
     pub fn new() -> ProcessHandle {
         Action::new(Self {
             name: "FAILURE".to_string(),
-        })
+        }).with_constant(false)
     }
 }
 
@@ -231,90 +364,3 @@ impl Executor for Failure {
         Ok(false)
     }
 }
-
-#[cfg(test)]
-pub(crate) mod mocking {
-
-    use anyhow::{anyhow, Result};
-    use tokio::time::{sleep, Duration};
-
-    use crate::nodes_bin::process_handle::ProcessHandle;
-
-    use super::{Action, Executor};
-
-    // The Mock action is intended to completely mock all logic of a normal action, but does not execute anything complex.
-    pub struct MockAction {
-        name: String,
-        calls: i32,
-        succeed: bool,
-        throw_error: bool,
-        fail_on_twice: bool,
-        keep_looping: bool,
-    }
-
-    #[allow(dead_code)]
-    impl MockAction {
-        pub fn new(id: i32) -> ProcessHandle {
-            Action::new(Self::_new(id, true, false, false, false))
-        }
-
-        pub fn new_loop(id: i32) -> ProcessHandle {
-            Action::new(Self::_new(id, true, false, false, true))
-        }
-
-        pub fn new_failing(id: i32) -> ProcessHandle {
-            Action::new(Self::_new(id, false, false, false, false))
-        }
-
-        pub fn fail_on_twice(id: i32) -> ProcessHandle {
-            Action::new(Self::_new(id, true, false, true, false))
-        }
-
-        pub fn new_error(id: i32) -> ProcessHandle {
-            Action::new(Self::_new(id, true, true, false, false))
-        }
-
-        fn _new(
-            id: i32,
-            succeed: bool,
-            throw_error: bool,
-            fail_on_twice: bool,
-            keep_looping: bool,
-        ) -> Self {
-            Self {
-                calls: 0,
-                name: id.to_string(),
-                succeed,
-                throw_error,
-                fail_on_twice,
-                keep_looping,
-            }
-        }
-    }
-
-    impl Executor for MockAction {
-        fn get_name(&self) -> String {
-            self.name.clone()
-        }
-
-        async fn execute(&mut self) -> Result<bool> {
-            self.calls += 1;
-
-            loop {
-                sleep(Duration::from_millis(500)).await;
-
-                if !self.keep_looping {
-                    break;
-                }
-            }
-
-            if self.throw_error {
-                Err(anyhow!("Some testing error!"))
-            } else if self.fail_on_twice {
-                Ok(self.calls < 2)
-            } else {
-                Ok(self.succeed)
-            }
-        }
-    }
-}
@@ -0,0 +1,86 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use tokio::runtime::Handle;
+
+use crate::nodes::action::Executor;
+
+// For synchronous or CPU heavy work, which would otherwise block the runtime that serves the other nodes
+pub trait BlockingExecutor {
+    fn get_name(&self) -> String;
+    // Check the flag regularly and return early once it is cancelled, the result is ignored then
+    fn execute(&mut self, cancel: &CancelFlag) -> Result<bool>;
+}
+
+// Raised when the action is stopped or killed while executing. A new flag is made for every execution
+#[derive(Debug, Clone, Default)]
+pub struct CancelFlag(Arc<AtomicBool>);
+
+impl CancelFlag {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+// Cancels the execution if the engine drops it before it finished
+struct CancelOnDrop(Option<CancelFlag>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(cancel) = self.0.take() {
+            cancel.cancel();
+        }
+    }
+}
+
+// Runs a BlockingExecutor on the blocking thread pool of a runtime
+pub(crate) struct BlockingAdapter<T> {
+    name: String,
+    inner: Arc<Mutex<T>>, // Shared with the blocking thread, which may outlive a cancelled execution
+    pool: Option<Handle>, // The runtime of the action if None
+}
+
+impl<T> BlockingAdapter<T>
+where
+    T: BlockingExecutor + Send + 'static,
+{
+    pub(crate) fn new(inner: T, pool: Option<Handle>) -> BlockingAdapter<T> {
+        Self {
+            name: inner.get_name(),
+            inner: Arc::new(Mutex::new(inner)),
+            pool,
+        }
+    }
+}
+
+impl<T> Executor for BlockingAdapter<T>
+where
+    T: BlockingExecutor + Send + 'static,
+{
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn execute(&mut self) -> Result<bool> {
+        let cancel = CancelFlag::default();
+        let mut guard = CancelOnDrop(Some(cancel.clone()));
+
+        let inner = self.inner.clone();
+        let work = move || match inner.lock() {
+            Ok(mut inner) => inner.execute(&cancel),
+            Err(_) => Err(anyhow!("Executor panicked in an earlier execution")),
+        };
+        let res = match &self.pool {
+            Some(pool) => pool.spawn_blocking(work).await,
+            None => tokio::task::spawn_blocking(work).await,
+        };
+
+        guard.0 = None; // Finished, nothing to cancel
+        res?
+    }
+}
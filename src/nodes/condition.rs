@@ -1,14 +1,18 @@
-use actify::{CacheRecvNewestError, Handle};
-use anyhow::Result;
-use std::fmt::Debug;
+use actify::CacheRecvNewestError;
+use anyhow::{Result, bail};
+use std::any::{Any, TypeId, type_name};
+use std::fmt::{self, Debug};
 use std::future::Future;
 use std::marker::PhantomData;
+use std::sync::Arc;
 use tokio::sync::broadcast::{channel, Receiver, Sender};
+use tokio::time::{sleep_until, Duration, Instant};
 
+use crate::nodes::watch::{Watch, WatchCache};
 use crate::nodes_bin::{
     node::{NodeProcess},
     node_error::NodeError,
-    process_handle::ProcessHandle,
+    process_handle::{ProcessHandle, ProcessName},
     node_message::{ChildMessage, ParentMessage},
     node_status::Status,
 };
@@ -59,65 +63,176 @@ where
     }
 }
 
+// Filters the results of a condition before they reach the engine, against noisy inputs
+#[derive(Debug, Clone, Default)]
+pub struct ConditionOptions {
+    debounce: Option<Duration>,
+    min_flip_interval: Option<Duration>,
+    hysteresis: Option<Thresholds>,
+    max_age: Option<Duration>,
+    stale_result: bool,
+}
+
+impl ConditionOptions {
+    pub fn new() -> ConditionOptions {
+        Self::default()
+    }
+
+    // A new result has to hold this long before it is reported
+    pub fn debounce(mut self, hold: Duration) -> Self {
+        self.debounce = Some(hold);
+        self
+    }
+
+    // Limits the flip rate: a flip within this interval after the previous one is delayed until the interval passed
+    pub fn min_flip_interval(mut self, interval: Duration) -> Self {
+        self.min_flip_interval = Some(interval);
+        self
+    }
+
+    // Only lets the result flip to success once the value rises above `rising`, and back to failure once it
+    // drops below `falling`, whatever the evaluator decided. The thresholds have the type of the watched value
+    pub fn hysteresis<V>(mut self, rising: V, falling: V) -> Result<Self>
+    where
+        V: PartialOrd + Debug + Send + Sync + 'static,
+    {
+        if rising < falling {
+            bail!("Hysteresis needs rising ({rising:?}) >= falling ({falling:?})");
+        }
+        self.hysteresis = Some(Thresholds::new(rising, falling));
+        Ok(self)
+    }
+
+    // Without a new value within this window the input is stale, and the condition reports the stale result
+    // until a new value arrives. The transition is reported right away, regardless of the other options
+    pub fn max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+
+    // Result while the input is stale, failure by default
+    pub fn stale_result(mut self, result: bool) -> Self {
+        self.stale_result = result;
+        self
+    }
+}
+
+type Allows = Arc<dyn Fn(&dyn Any, bool) -> bool + Send + Sync>;
+
+// Rising and falling thresholds of the hysteresis, compared against values of the type they were given in
+#[derive(Clone)]
+struct Thresholds {
+    allows: Allows, // Whether the value lets the result flip to the given one
+    value_type: (TypeId, &'static str),
+    bounds: String,
+}
+
+impl Thresholds {
+    fn new<V>(rising: V, falling: V) -> Thresholds
+    where
+        V: PartialOrd + Debug + Send + Sync + 'static,
+    {
+        let bounds = format!("rising: {rising:?}, falling: {falling:?}");
+        let allows = move |val: &dyn Any, res: bool| match val.downcast_ref::<V>() {
+            Some(val) if res => *val > rising,
+            Some(val) => *val < falling,
+            None => true, // Checked when the condition is created
+        };
+        Self { allows: Arc::new(allows), value_type: (TypeId::of::<V>(), type_name::<V>()), bounds }
+    }
+}
+
+impl Debug for Thresholds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Thresholds {{ {} }}", self.bounds)
+    }
+}
+
 pub struct Condition {}
 
+// The handle can also be a tuple of handles, e.g. (battery, estop), then the evaluator gets a tuple of values
 impl Condition {
-    pub fn new_from<V, T>(evaluator: T, handle: Handle<V>) -> ProcessHandle
+    pub fn new_from<W, T>(evaluator: T, handle: W) -> ProcessHandle
     where
-        T: Evaluator<V> + Clone + Send + Sync + 'static,
-        V: Clone + Debug + Send + Sync + Clone + 'static,
+        T: Evaluator<W::Value> + Clone + Send + Sync + 'static,
+        W: Watch,
     {
-        ConditionProcess::new(handle, evaluator)
+        ConditionProcess::new(handle, evaluator, ConditionOptions::default())
     }
 
-    pub fn new<V, S, F>(name: S, handle: Handle<V>, function: F) -> ProcessHandle
+    pub fn new_with<W, T>(evaluator: T, handle: W, options: ConditionOptions) -> ProcessHandle
+    where
+        T: Evaluator<W::Value> + Clone + Send + Sync + 'static,
+        W: Watch,
+    {
+        ConditionProcess::new(handle, evaluator, options)
+    }
+
+    pub fn new<W, S, F>(name: S, handle: W, function: F) -> ProcessHandle
     where
         S: Into<String> + Clone,
-        F: Fn(V) -> bool + Sync + Send + Clone + 'static,
-        V: Clone + Debug + Send + Sync + Clone + 'static,
+        F: Fn(W::Value) -> bool + Sync + Send + Clone + 'static,
+        W: Watch,
     {
         let evaluator = ClosureEvaluator::new(name.into(), function);
-        ConditionProcess::new(handle, evaluator)
+        ConditionProcess::new(handle, evaluator, ConditionOptions::default())
     }
 }
 
-struct ConditionProcess<V, T>
+struct ConditionProcess<W, T>
 where
-    T: Evaluator<V> + Clone + Send + Sync + 'static,
+    W: Watch,
+    T: Evaluator<W::Value> + Clone + Send + Sync + 'static,
 {
-    handle: Handle<V>,
+    handle: W,
     tx: Sender<ParentMessage>,
     rx: Receiver<ChildMessage>,
     status: Status,
     evaluator: T,
-    prev_evaluation: bool,
+    options: ConditionOptions,
+    pending: Option<(bool, Instant)>, // Result that is held back by the options, with the time it can be reported
+    last_flip: Option<Instant>,
+    last_value: Instant, // When the newest value arrived or the monitoring started, for the max age
+    stale: bool,
+    name: ProcessName,
 }
 
-impl<V, T> ConditionProcess<V, T>
+impl<W, T> ConditionProcess<W, T>
 where
-    T: Evaluator<V> + Clone + Send + Sync + 'static,
-    V: Clone + Debug + Send + Sync + Clone + 'static,
+    W: Watch,
+    T: Evaluator<W::Value> + Clone + Send + Sync + 'static,
 {
-    pub fn new(handle: Handle<V>, evaluator: T) -> ProcessHandle {
+    pub fn new(handle: W, evaluator: T, mut options: ConditionOptions) -> ProcessHandle {
+        if let Some(thresholds) = options.hysteresis.take_if(|t| t.value_type.0 != TypeId::of::<W::Value>()) {
+            log::error!(
+                "Condition {:?} - ignoring the hysteresis, its thresholds are {} but the value is {}",
+                evaluator.get_name(), thresholds.value_type.1, type_name::<W::Value>()
+            );
+        }
         let (parent_tx, parent_rx) = channel(CHANNEL_SIZE);
         let (child_tx, child_rx) = channel(CHANNEL_SIZE);
 
+        let name = ProcessName::new(evaluator.get_name());
         let node = Self::_new(
-            evaluator.clone(),
+            evaluator,
             handle,
+            options,
             parent_tx.clone(),
             child_rx,
+            name.clone(),
         );
         tokio::spawn(Self::serve(node));
 
-        ProcessHandle::new(child_tx, parent_rx, evaluator.get_name())
+        ProcessHandle::new(child_tx, parent_rx, name)
     }
 
     fn _new(
         evaluator: T,
-        handle: Handle<V>,
+        handle: W,
+        options: ConditionOptions,
         tx: Sender<ParentMessage>,
         rx: Receiver<ChildMessage>,
+        name: ProcessName,
     ) -> Self {
         Self {
             handle,
@@ -125,7 +240,12 @@ where
             tx,
             rx,
             status: Status::Idle,
-            prev_evaluation: false,
+            options,
+            pending: None,
+            last_flip: None,
+            last_value: Instant::now(),
+            stale: false,
+            name,
         }
     }
 
@@ -138,7 +258,7 @@ where
     fn notify_parent(&mut self, msg: ParentMessage) -> Result<(), NodeError> {
         log::debug!(
             "Condition {:?} - notify parent: {:?}",
-            self.evaluator.get_name(),
+            self.name,
             msg
         );
         self.tx.send(msg)?;
@@ -147,7 +267,7 @@ where
 
     async fn process_incoming_val(
         &mut self,
-        val: Result<V, CacheRecvNewestError>,
+        val: Result<W::Value, CacheRecvNewestError>,
     ) -> Result<(), NodeError> {
 
         // Skip errors
@@ -158,17 +278,13 @@ where
             }
             Ok(v) => v,
         };
+        self.last_value = Instant::now();
+        self.stale = false;
 
         match self.status {
-            Status::Failure => {
-                if !self.prev_evaluation && self.run_evaluator(val.clone()).await? {
-                    self.update_status(Status::Success)?;
-                }
-            }
-            Status::Success => {
-                if self.prev_evaluation && !self.run_evaluator(val.clone()).await? {
-                    self.update_status(Status::Failure)?;
-                }
+            Status::Success | Status::Failure => {
+                let res = self.run_evaluator(val).await?;
+                self.request_flip(res)?;
             }
             Status::Running => {} // Conditions should never be Running
             Status::Idle => {}
@@ -176,10 +292,61 @@ where
         Ok(())
     }
 
+    // Flips the status if the options allow it now, otherwise holds the result back until they do
+    fn request_flip(&mut self, res: bool) -> Result<(), NodeError> {
+        if res == self.status.is_succes() {
+            self.pending = None; // Bounced back before it was reported
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let deadline = match self.pending {
+            Some((pending, deadline)) if pending == res => deadline, // Holding since the first time
+            _ => {
+                let mut deadline = now + self.options.debounce.unwrap_or_default();
+                if let (Some(interval), Some(last_flip)) = (self.options.min_flip_interval, self.last_flip) {
+                    deadline = deadline.max(last_flip + interval);
+                }
+                deadline
+            }
+        };
+
+        self.pending = Some((res, deadline));
+        if deadline <= now {
+            self.flush_pending()?;
+        }
+        Ok(())
+    }
+
+    // Always reports the staleness, and flips the status as well if the stale result differs from it
+    fn mark_stale(&mut self) -> Result<(), NodeError> {
+        log::warn!("Condition {:?} - no new value within {:?}", self.name, self.options.max_age);
+        self.stale = true;
+        self.pending = None;
+        let res = self.options.stale_result;
+        if matches!(self.status, Status::Success | Status::Failure) {
+            self.notify_parent(ParentMessage::Stale(res))?;
+            if res != self.status.is_succes() {
+                self.last_flip = Some(Instant::now());
+                self.update_status(res.into())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush_pending(&mut self) -> Result<(), NodeError> {
+        if let Some((res, _)) = self.pending.take() {
+            self.last_flip = Some(Instant::now());
+            self.update_status(res.into())?;
+        }
+        Ok(())
+    }
+
     async fn process_msg_from_parent(&mut self, msg: ChildMessage) -> Result<(), NodeError> {
         match msg {
             ChildMessage::Start => self.start_workflow().await?,
             ChildMessage::Stop => {
+                self.pending = None;
                 let _ = self.stop_workflow().await?;
                 self.update_status(Status::Idle)?;
             }
@@ -189,7 +356,17 @@ where
     }
 
     async fn start_workflow(&mut self) -> Result<(), NodeError> {
-        if !self.status.is_running() {
+        self.pending = None;
+        if self.status.is_idle() {
+            // The max age counts from the start of the monitoring, not from the creation of the tree
+            self.last_value = Instant::now();
+            self.stale = false;
+        }
+        if self.stale {
+            let res = self.options.stale_result;
+            self.notify_parent(ParentMessage::Stale(res))?;
+            self.update_status(res.into())?;
+        } else if !self.status.is_running() {
             match self.evaluate_now().await? {
                 true => self.update_status(Status::Success)?, // Without a child a condition succeeds immediately
                 false => self.update_status(Status::Failure)?, // Send failure to parent
@@ -202,13 +379,18 @@ where
         Ok(Status::Failure) // Default failure
     }
 
-    async fn run_evaluator(&mut self, val: V) -> Result<bool, NodeError> {
-        match self.evaluator.evaluate(val).await {
-            Ok(res) => {
-                self.prev_evaluation = res;
-                Ok(res)
-            }
-            Err(e) => Err(NodeError::ExecutionError(e.to_string())),
+    async fn run_evaluator(&mut self, val: W::Value) -> Result<bool, NodeError> {
+        let thresholds = self.options.hysteresis.clone().map(|thresholds| (thresholds, val.clone()));
+        let res = self.evaluator
+            .evaluate(val)
+            .await
+            .map_err(|e| NodeError::ExecutionError(e.to_string()))?;
+
+        // The first result after the start is the one of the evaluator, later flips have to pass the thresholds
+        let current = matches!(self.status, Status::Success | Status::Failure).then(|| self.status.is_succes());
+        match (thresholds, current) {
+            (Some((thresholds, val)), Some(current)) if res != current && !(thresholds.allows)(&val, res) => Ok(current),
+            _ => Ok(res),
         }
     }
 
@@ -220,23 +402,27 @@ where
     async fn _serve(mut self) -> Result<(), NodeError> {
         let mut cache = self.handle.create_cache().await;
         loop {
+            let deadline = self.pending.map_or_else(Instant::now, |(_, deadline)| deadline);
+            let stale_at = self.last_value + self.options.max_age.unwrap_or_default();
             tokio::select! {
                 Ok(msg) = self.rx.recv() => self.process_msg_from_parent(msg).await?,
-                res = cache.recv_newest() => self.process_incoming_val(res.cloned()).await?,
+                res = cache.recv_newest() => self.process_incoming_val(res).await?,
+                _ = sleep_until(deadline), if self.pending.is_some() => self.flush_pending()?,
+                _ = sleep_until(stale_at), if self.options.max_age.is_some() && !self.stale && !self.status.is_idle() => self.mark_stale()?,
                 else => log::warn!("Only invalid messages received"),
             };
         }
     }
 }
 
-impl<V, T> NodeProcess for ConditionProcess<V, T>
+impl<W, T> NodeProcess for ConditionProcess<W, T>
 where
-    T: Evaluator<V> + Clone + Send + Sync + 'static,
-    V: Clone + Debug + Send + Sync + Clone + 'static,
+    W: Watch,
+    T: Evaluator<W::Value> + Clone + Send + Sync + 'static,
 {
     async fn serve(self) {
         let poison_tx = self.tx.clone();
-        let name = self.evaluator.get_name();
+        let name = self.name.clone();
         let res = Self::_serve(self).await;
 
         log::debug!("Condition {name:?} exited with error: {res:?}");
@@ -257,7 +443,7 @@ where
     }
 }
 
-fn poison_parent(poison_tx: Sender<ParentMessage>, name: String, err: String) {
+fn poison_parent(poison_tx: Sender<ParentMessage>, name: ProcessName, err: String) {
     log::debug!("Condition {name:?} - poisoning parent");
     if let Err(e) = poison_tx.send(ParentMessage::Poison(NodeError::PoisonError(err))) {
         log::warn!("Condition {name:?} - poisoning the parent failed! {e:?}")
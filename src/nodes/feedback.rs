@@ -0,0 +1,66 @@
+use anyhow::Result;
+use serde::Serialize;
+use tokio::sync::broadcast::Sender;
+
+use crate::nodes_bin::node_message::ParentMessage;
+
+// Intermediate result of a running action, forwarded to the subscribers of the tree events
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct Progress {
+    pub percentage: Option<f32>,
+    pub message: Option<String>,
+    pub value: Option<serde_json::Value>,
+}
+
+impl Progress {
+    pub fn percentage(percentage: f32) -> Progress {
+        Self {
+            percentage: Some(percentage),
+            ..Default::default()
+        }
+    }
+
+    pub fn message(message: impl Into<String>) -> Progress {
+        Self {
+            message: Some(message.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn value<T: Serialize>(value: &T) -> Result<Progress> {
+        Ok(Self {
+            value: Some(serde_json::to_value(value)?),
+            ..Default::default()
+        })
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Progress {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+// Handed to an executor when its action is created, to publish progress while it is executing
+#[derive(Debug, Clone)]
+pub struct Feedback {
+    tx: Sender<ParentMessage>,
+}
+
+impl Feedback {
+    pub(crate) fn new(tx: Sender<ParentMessage>) -> Feedback {
+        Self { tx }
+    }
+
+    pub fn send(&self, progress: Progress) {
+        log::trace!("Progress: {:?}", progress);
+        let _ = self.tx.send(ParentMessage::Progress(progress)); // Fails only without an engine listening
+    }
+
+    pub fn percentage(&self, percentage: f32) {
+        self.send(Progress::percentage(percentage))
+    }
+
+    pub fn message(&self, message: impl Into<String>) {
+        self.send(Progress::message(message))
+    }
+}
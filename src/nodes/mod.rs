@@ -1,2 +1,7 @@
 pub mod action;
-pub mod condition;
\ No newline at end of file
+pub mod blocking;
+pub mod condition;
+pub mod feedback;
+pub mod switch;
+pub mod utility;
+pub mod watch;
\ No newline at end of file
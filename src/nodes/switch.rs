@@ -0,0 +1,175 @@
+use actify::CacheRecvNewestError;
+use tokio::sync::broadcast::{Receiver, Sender, channel};
+
+use crate::{BT, Builder, bt::CHANNEL_SIZE, nodes::watch::{Watch, WatchCache}};
+use crate::nodes_bin::{node::NodeProcess, node_error::NodeError, node_message::{ChildMessage, ParentMessage}, node_status::Status, process_handle::{ProcessHandle, ProcessName}};
+
+// Branches of a switch node on the value of one handle, see BT::switch().
+// The first case equal to the value is selected, otherwise the default. Without a default the switch fails
+pub struct Switch<W: Watch> {
+    name: String,
+    handle: W,
+    cases: Vec<W::Value>,
+    children: Vec<BT<Builder>>,
+    default: Option<BT<Builder>>,
+}
+
+impl<W> Switch<W>
+where
+    W: Watch,
+    W::Value: PartialEq,
+{
+    pub fn new(name: impl Into<String>, handle: W) -> Switch<W> {
+        Self {
+            name: name.into(),
+            handle,
+            cases: vec![],
+            children: vec![],
+            default: None,
+        }
+    }
+
+    pub fn case(mut self, value: W::Value, tree: BT<Builder>) -> Self {
+        self.cases.push(value);
+        self.children.push(tree);
+        self
+    }
+
+    pub fn default(mut self, tree: BT<Builder>) -> Self {
+        self.default = Some(tree);
+        self
+    }
+
+    // The process of the switch and its children, the default comes last
+    pub(crate) fn into_parts(mut self) -> (ProcessHandle, Vec<BT<Builder>>) {
+        let cases = Cases { cases: self.cases, has_default: self.default.is_some() };
+        self.children.extend(self.default);
+        (SwitchProcess::spawn(self.name, self.handle, cases), self.children)
+    }
+}
+
+// Picks the branch of a switch process from the watched value, None if no branch matches
+pub(crate) trait Selector<V>: Send + Sync + 'static {
+    fn select(&mut self, val: &V) -> Option<usize>;
+
+    // Called when the switch is stopped, the next selection starts over
+    fn reset(&mut self) {}
+}
+
+struct Cases<V> {
+    cases: Vec<V>,
+    has_default: bool,
+}
+
+impl<V: PartialEq + Send + Sync + 'static> Selector<V> for Cases<V> {
+    fn select(&mut self, val: &V) -> Option<usize> {
+        let default = self.has_default.then_some(self.cases.len());
+        self.cases.iter().position(|case| case == val).or(default)
+    }
+}
+
+// Reports the selected branch when started, and every time it changes until stopped
+pub(crate) struct SwitchProcess<W: Watch, S: Selector<W::Value>> {
+    name: ProcessName,
+    handle: W,
+    selector: S,
+    tx: Sender<ParentMessage>,
+    rx: Receiver<ChildMessage>,
+    selected: Option<Option<usize>>, // None while idle, Some(None) when no branch matched
+}
+
+impl<W, S> SwitchProcess<W, S>
+where
+    W: Watch,
+    S: Selector<W::Value>,
+{
+    // Spawns the process and returns the handle the engine talks to it with
+    pub(crate) fn spawn(name: String, handle: W, selector: S) -> ProcessHandle {
+        let (parent_tx, parent_rx) = channel(CHANNEL_SIZE);
+        let (child_tx, child_rx) = channel(CHANNEL_SIZE);
+        let name = ProcessName::new(name);
+
+        let node = Self {
+            name: name.clone(),
+            handle,
+            selector,
+            tx: parent_tx,
+            rx: child_rx,
+            selected: None,
+        };
+        tokio::spawn(node.serve());
+
+        ProcessHandle::new(child_tx, parent_rx, name)
+    }
+
+    fn report(&mut self, selected: Option<usize>) -> Result<(), NodeError> {
+        self.selected = Some(selected);
+        let msg = match selected {
+            Some(branch) => ParentMessage::Branch(branch),
+            None => ParentMessage::Status(Status::Failure),
+        };
+        log::debug!("Switch {:?} - notify parent: {:?}", self.name, msg);
+        self.tx.send(msg)?;
+        Ok(())
+    }
+
+    async fn process_msg_from_parent(&mut self, msg: ChildMessage) -> Result<(), NodeError> {
+        match msg {
+            ChildMessage::Start => {
+                let val = self.handle.get().await;
+                let selected = self.selector.select(&val);
+                self.report(selected)?;
+            }
+            ChildMessage::Stop => {
+                self.selected = None;
+                self.selector.reset();
+                self.tx.send(ParentMessage::Status(Status::Idle))?;
+            }
+            ChildMessage::Kill => return Err(NodeError::KillError),
+        }
+        Ok(())
+    }
+
+    fn process_incoming_val(&mut self, val: Result<W::Value, CacheRecvNewestError>) -> Result<(), NodeError> {
+        let (Ok(val), Some(current)) = (val, self.selected) else { return Ok(()) }; // Not selecting while idle
+        let selected = self.selector.select(&val);
+        if current != selected {
+            self.report(selected)?;
+        }
+        Ok(())
+    }
+
+    async fn _serve(mut self) -> Result<(), NodeError> {
+        let mut cache = self.handle.create_cache().await;
+        loop {
+            tokio::select! {
+                Ok(msg) = self.rx.recv() => self.process_msg_from_parent(msg).await?,
+                res = cache.recv_newest() => self.process_incoming_val(res)?,
+                else => log::warn!("Only invalid messages received"),
+            };
+        }
+    }
+}
+
+impl<W, S> NodeProcess for SwitchProcess<W, S>
+where
+    W: Watch,
+    S: Selector<W::Value>,
+{
+    async fn serve(self) {
+        let tx = self.tx.clone();
+        let name = self.name.clone();
+        let res = Self::_serve(self).await;
+
+        log::debug!("Switch {name:?} exited with error: {res:?}");
+
+        let msg = match res {
+            Err(NodeError::KillError) => ParentMessage::Killed,
+            Err(err) => ParentMessage::Poison(NodeError::PoisonError(err.to_string())),
+            Ok(_) => return, // Should never occur
+        };
+        if let Err(e) = tx.send(msg) {
+            log::warn!("Switch {name:?} - notifying the parent failed! {e:?}")
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{BT, Builder, nodes::{switch::{Selector, SwitchProcess}, watch::Watch}, nodes_bin::process_handle::ProcessHandle};
+
+type Scorer<V> = Box<dyn Fn(&V) -> f64 + Send + Sync>;
+
+// Children of a utility selector with their scores on the value of one handle, see BT::utility().
+// The selected child only changes when another child scores more than the margin above it
+pub struct UtilitySelector<W: Watch> {
+    name: String,
+    handle: W,
+    margin: f64,
+    scorers: Vec<Scorer<W::Value>>,
+    children: Vec<BT<Builder>>,
+}
+
+impl<W: Watch> UtilitySelector<W> {
+    pub fn new(name: impl Into<String>, handle: W) -> UtilitySelector<W> {
+        Self {
+            name: name.into(),
+            handle,
+            margin: 0.0,
+            scorers: vec![],
+            children: vec![],
+        }
+    }
+
+    pub fn margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn child<F>(mut self, score: F, tree: BT<Builder>) -> Self
+    where
+        F: Fn(&W::Value) -> f64 + Send + Sync + 'static,
+    {
+        self.scorers.push(Box::new(score));
+        self.children.push(tree);
+        self
+    }
+
+    // A switch process that selects the child by the scores, and the children
+    pub(crate) fn into_parts(self) -> (ProcessHandle, Vec<BT<Builder>>) {
+        let scores = Scores {
+            scorers: self.scorers,
+            margin: self.margin,
+            selected: None,
+        };
+        (SwitchProcess::spawn(self.name, self.handle, scores), self.children)
+    }
+}
+
+// The selection of the one process, so a change of the scores switches the child once
+struct Scores<V> {
+    scorers: Vec<Scorer<V>>,
+    margin: f64,
+    selected: Option<usize>, // Kept while the selector is monitored, forgotten when it is stopped
+}
+
+impl<V: Send + Sync + 'static> Selector<V> for Scores<V> {
+    fn select(&mut self, val: &V) -> Option<usize> {
+        let scores: Vec<f64> = self.scorers.iter().map(|score| score(val)).collect();
+        let best = (0..scores.len()).reduce(|best, i| if scores[i] > scores[best] { i } else { best })?;
+
+        let keep = self.selected.filter(|current| scores[best] <= scores[*current] + self.margin);
+        self.selected = keep.or(Some(best));
+        self.selected
+    }
+
+    fn reset(&mut self) {
+        self.selected = None;
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt::Debug;
+use std::future::Future;
+
+use actify::{Cache, CacheRecvNewestError, Handle};
+
+// The values a condition evaluates: a single Handle, or a tuple of handles that re-evaluates when any of them changes
+pub trait Watch: Clone + Send + Sync + 'static {
+    type Value: Clone + Debug + Send + Sync + 'static;
+    type Cache: WatchCache<Value = Self::Value>;
+
+    fn get(&self) -> impl Future<Output = Self::Value> + Send;
+    fn create_cache(&self) -> impl Future<Output = Self::Cache> + Send;
+}
+
+pub trait WatchCache: Send + 'static {
+    type Value;
+
+    // Waits until any of the watched values changes, then returns the newest values of all of them
+    fn recv_newest(&mut self) -> impl Future<Output = Result<Self::Value, CacheRecvNewestError>> + Send;
+}
+
+impl<V> Watch for Handle<V>
+where
+    V: Clone + Debug + Send + Sync + 'static,
+{
+    type Value = V;
+    type Cache = Cache<V>;
+
+    async fn get(&self) -> V {
+        Handle::get(self).await
+    }
+
+    async fn create_cache(&self) -> Cache<V> {
+        Handle::create_cache(self).await
+    }
+}
+
+impl<V> WatchCache for Cache<V>
+where
+    V: Clone + Debug + Send + Sync + 'static,
+{
+    type Value = V;
+
+    async fn recv_newest(&mut self) -> Result<V, CacheRecvNewestError> {
+        Cache::recv_newest(self).await.cloned()
+    }
+}
+
+// Caches of a tuple of handles, together with the newest value of each
+pub struct TupleCache<C, V> {
+    caches: C,
+    values: V,
+}
+
+macro_rules! impl_watch_for_tuple {
+    ($($T:ident $idx:tt),+) => {
+        impl<$($T),+> Watch for ($(Handle<$T>,)+)
+        where
+            $($T: Clone + Debug + Send + Sync + 'static),+
+        {
+            type Value = ($($T,)+);
+            type Cache = TupleCache<($(Cache<$T>,)+), ($($T,)+)>;
+
+            async fn get(&self) -> Self::Value {
+                ($(self.$idx.get().await,)+)
+            }
+
+            async fn create_cache(&self) -> Self::Cache {
+                TupleCache {
+                    caches: ($(self.$idx.create_cache().await,)+),
+                    values: Watch::get(self).await,
+                }
+            }
+        }
+
+        impl<$($T),+> WatchCache for TupleCache<($(Cache<$T>,)+), ($($T,)+)>
+        where
+            $($T: Clone + Debug + Send + Sync + 'static),+
+        {
+            type Value = ($($T,)+);
+
+            async fn recv_newest(&mut self) -> Result<Self::Value, CacheRecvNewestError> {
+                let (caches, values) = (&mut self.caches, &mut self.values);
+                tokio::select! {
+                    $(res = caches.$idx.recv_newest() => values.$idx = res?.clone(),)+
+                }
+                Ok(values.clone())
+            }
+        }
+    };
+}
+
+impl_watch_for_tuple!(A 0, B 1);
+impl_watch_for_tuple!(A 0, B 1, C 2);
+impl_watch_for_tuple!(A 0, B 1, C 2, D 3);
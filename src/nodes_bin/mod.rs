@@ -3,4 +3,6 @@ pub mod process_handle;
 pub(super) mod node_message;
 pub(super) mod node_status;
 pub(super) mod node;
+pub(super) mod node_arena;
+pub(super) mod node_info;
 pub(super) mod node_map;
\ No newline at end of file
@@ -4,21 +4,23 @@ pub trait NodeProcess: Sync + Send {
 
 pub(crate) type NodeId = String;
 
+// Nested description of a tree, used to write out the expected trees in tests
+#[cfg(test)]
 #[derive(Debug, Clone, serde::Serialize, PartialEq, Eq, Hash)]
 pub enum Node {
     Action(NodeId),
     Condition(NodeId),
     Sequence(Vec<Node>),
     Fallback(Vec<Node>),
+    SequenceWithMemory(Vec<Node>),
+    FallbackWithMemory(Vec<Node>),
+    RandomSequence(Vec<Node>),
+    RandomFallback(Vec<Node>),
+    Switch(NodeId, Vec<Node>),
+    RunOnce(Box<Node>),
+    Delay(NodeId, Box<Node>),
+    KeepRunningUntilFailure(Box<Node>),
+    Cooldown(std::time::Duration, Box<Node>),
+    RateLimit(u32, std::time::Duration, Box<Node>),
+    WeightedChoice(Vec<(u32, Node)>), // Integer weights keep the test trees comparable
 }
-
-impl Node {
-    pub fn get_id(&self) -> Option<String> {
-        match self {
-            Node::Action(id) => Some(id.to_string()),
-            Node::Condition(id) => Some(id.to_string()),
-            Node::Sequence(_) => None,
-            Node::Fallback(_) => None,
-        }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,260 @@
+use tokio::time::Duration;
+
+use crate::nodes_bin::{node::NodeId, node_info::NodeMeta};
+#[cfg(test)]
+use crate::nodes_bin::node::Node;
+
+// Handle to a node in the arena, cheap to copy, compare and hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeIndex(usize);
+
+impl NodeIndex {
+    pub(crate) fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum NodeKind {
+    Action(NodeId),
+    Condition(NodeId),
+    Sequence,
+    Fallback,
+    SequenceWithMemory, // Skips the children that already succeeded when it is entered again
+    FallbackWithMemory, // Skips the children that already failed when it is entered again
+    RandomSequence, // Sequence over its children in a random order
+    RandomFallback, // Fallback over its children in a random order
+    WeightedChoice, // Runs one child, picked at random by the weights of the children
+    Switch(NodeId), // Runs the child selected by its process, which watches a handle like a condition
+    RunOnce, // Runs its child once, afterwards it returns the same result right away
+    Cooldown(Duration), // Fails without running its child until the period passed since the child finished
+    RateLimit(u32, Duration), // Fails without running its child if it started the limit already within the period
+    Delay(NodeId), // Its process waits before the child starts, and is stopped like an action
+    KeepRunningUntilFailure, // Restarts its child every time it succeeds, fails when the child fails
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ArenaNode {
+    pub kind: NodeKind,
+    pub parent: Option<NodeIndex>,
+    pub children: Vec<NodeIndex>,
+    pub position: usize, // Position of this node among the children of its parent
+    pub checked_once: bool, // Conditions only, not monitored after they finished
+    pub weight: f64, // Chance of being picked by a parent WeightedChoice, relative to its siblings
+    pub meta: NodeMeta,
+}
+
+// Flat storage of the tree, the root is always stored at index 0
+#[derive(Debug, Clone)]
+pub(crate) struct NodeArena {
+    nodes: Vec<ArenaNode>,
+}
+
+impl NodeArena {
+    pub(crate) fn leaf(kind: NodeKind) -> NodeArena {
+        Self {
+            nodes: vec![ArenaNode {
+                kind,
+                parent: None,
+                children: vec![],
+                position: 0,
+                checked_once: false,
+                weight: 1.0,
+                meta: NodeMeta::default(),
+            }],
+        }
+    }
+
+    // Appends the child arenas below a new root, shifting all their indices
+    pub(crate) fn composite(kind: NodeKind, children: Vec<NodeArena>) -> NodeArena {
+        let mut arena = Self::leaf(kind);
+        for (position, child) in children.into_iter().enumerate() {
+            let offset = arena.nodes.len();
+            arena.nodes[0].children.push(NodeIndex(offset));
+            arena.nodes.extend(child.nodes.into_iter().map(|mut node| {
+                node.parent = Some(node.parent.map_or(NodeIndex(0), |p| NodeIndex(p.0 + offset)));
+                node.children.iter_mut().for_each(|c| c.0 += offset);
+                node
+            }));
+            arena.nodes[offset].position = position;
+        }
+        arena
+    }
+
+    // Copy of the node with new children, keeping everything else that was set on it
+    pub(crate) fn rebuild(&self, idx: NodeIndex, children: Vec<NodeArena>) -> NodeArena {
+        let mut arena = NodeArena::composite(self.kind(idx).clone(), children);
+        let node = self.get(idx);
+        arena.nodes[0].checked_once = node.checked_once;
+        arena.nodes[0].weight = node.weight;
+        arena.nodes[0].meta = node.meta.clone();
+        arena
+    }
+
+    // Copy of the subtree below the given node, as an arena of its own
+    pub(crate) fn subtree(&self, idx: NodeIndex) -> NodeArena {
+        self.rebuild(idx, self.children(idx).iter().map(|child| self.subtree(*child)).collect())
+    }
+
+    pub(crate) fn root(&self) -> NodeIndex {
+        NodeIndex(0)
+    }
+
+    pub(crate) fn indices(&self) -> impl Iterator<Item = NodeIndex> + use<> {
+        (0..self.nodes.len()).map(NodeIndex)
+    }
+
+    pub(crate) fn get(&self, idx: NodeIndex) -> &ArenaNode {
+        &self.nodes[idx.0]
+    }
+
+    pub(crate) fn kind(&self, idx: NodeIndex) -> &NodeKind {
+        &self.get(idx).kind
+    }
+
+    pub(crate) fn get_id(&self, idx: NodeIndex) -> Option<&NodeId> {
+        match self.kind(idx) {
+            NodeKind::Action(id) | NodeKind::Condition(id) | NodeKind::Switch(id) | NodeKind::Delay(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    // Nodes the engines start and listen to: the leaves and the switches
+    pub(crate) fn has_process(&self, idx: NodeIndex) -> bool {
+        self.get_id(idx).is_some()
+    }
+
+    // Nodes that evaluate a handle when started, and can stay monitored after they finished
+    pub(crate) fn is_watcher(&self, idx: NodeIndex) -> bool {
+        matches!(self.kind(idx), NodeKind::Condition(_) | NodeKind::Switch(_))
+    }
+
+    pub(crate) fn is_reactive(&self, idx: NodeIndex) -> bool {
+        self.is_watcher(idx) && !self.get(idx).checked_once
+    }
+
+    pub(crate) fn set_checked_once(&mut self) {
+        self.nodes.iter_mut().for_each(|node| node.checked_once = true);
+    }
+
+    pub(crate) fn set_weight(&mut self, weight: f64) {
+        self.nodes[0].weight = weight;
+    }
+
+    pub(crate) fn root_meta_mut(&mut self) -> &mut NodeMeta {
+        &mut self.nodes[0].meta
+    }
+
+    pub(crate) fn parent(&self, idx: NodeIndex) -> Option<NodeIndex> {
+        self.get(idx).parent
+    }
+
+    pub(crate) fn children(&self, idx: NodeIndex) -> &[NodeIndex] {
+        &self.get(idx).children
+    }
+
+    pub(crate) fn next_sibling(&self, idx: NodeIndex) -> Option<NodeIndex> {
+        let node = self.get(idx);
+        let parent = node.parent?;
+        self.children(parent).get(node.position + 1).copied()
+    }
+
+    pub(crate) fn is_below(&self, idx: NodeIndex, ancestor: NodeIndex) -> bool {
+        let mut current = idx;
+        while let Some(parent) = self.parent(current) {
+            if parent == ancestor {
+                return true;
+            }
+            current = parent;
+        }
+        false
+    }
+
+    // Path from the root down to (and including) the given node
+    pub(crate) fn trace(&self, idx: NodeIndex) -> Vec<NodeIndex> {
+        let mut trace = vec![idx];
+        let mut current = idx;
+        while let Some(parent) = self.parent(current) {
+            trace.push(parent);
+            current = parent;
+        }
+        trace.reverse();
+        trace
+    }
+
+    // Rebuilds the nested representation of the subtree below the given node
+    #[cfg(test)]
+    pub(crate) fn to_node(&self, idx: NodeIndex) -> Node {
+        let children = || self.children(idx).iter().map(|c| self.to_node(*c)).collect();
+        match self.kind(idx) {
+            NodeKind::Action(id) => Node::Action(id.clone()),
+            NodeKind::Condition(id) => Node::Condition(id.clone()),
+            NodeKind::Sequence => Node::Sequence(children()),
+            NodeKind::Fallback => Node::Fallback(children()),
+            NodeKind::SequenceWithMemory => Node::SequenceWithMemory(children()),
+            NodeKind::FallbackWithMemory => Node::FallbackWithMemory(children()),
+            NodeKind::RandomSequence => Node::RandomSequence(children()),
+            NodeKind::RandomFallback => Node::RandomFallback(children()),
+            NodeKind::Switch(id) => Node::Switch(id.clone(), children()),
+            NodeKind::Delay(id) => Node::Delay(id.clone(), Box::new(self.to_node(self.children(idx)[0]))),
+            NodeKind::KeepRunningUntilFailure => Node::KeepRunningUntilFailure(Box::new(self.to_node(self.children(idx)[0]))),
+            NodeKind::RunOnce => Node::RunOnce(Box::new(self.to_node(self.children(idx)[0]))),
+            NodeKind::Cooldown(period) => Node::Cooldown(*period, Box::new(self.to_node(self.children(idx)[0]))),
+            NodeKind::RateLimit(limit, period) => Node::RateLimit(*limit, *period, Box::new(self.to_node(self.children(idx)[0]))),
+            NodeKind::WeightedChoice => Node::WeightedChoice(
+                self.children(idx).iter().map(|c| (self.get(*c).weight as u32, self.to_node(*c))).collect()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+impl From<Node> for NodeArena {
+    fn from(node: Node) -> NodeArena {
+        match node {
+            Node::Action(id) => NodeArena::leaf(NodeKind::Action(id)),
+            Node::Condition(id) => NodeArena::leaf(NodeKind::Condition(id)),
+            Node::Sequence(children) => NodeArena::composite(
+                NodeKind::Sequence,
+                children.into_iter().map(NodeArena::from).collect(),
+            ),
+            Node::Fallback(children) => NodeArena::composite(
+                NodeKind::Fallback,
+                children.into_iter().map(NodeArena::from).collect(),
+            ),
+            Node::SequenceWithMemory(children) => NodeArena::composite(
+                NodeKind::SequenceWithMemory,
+                children.into_iter().map(NodeArena::from).collect(),
+            ),
+            Node::FallbackWithMemory(children) => NodeArena::composite(
+                NodeKind::FallbackWithMemory,
+                children.into_iter().map(NodeArena::from).collect(),
+            ),
+            Node::RandomSequence(children) => NodeArena::composite(
+                NodeKind::RandomSequence,
+                children.into_iter().map(NodeArena::from).collect(),
+            ),
+            Node::RandomFallback(children) => NodeArena::composite(
+                NodeKind::RandomFallback,
+                children.into_iter().map(NodeArena::from).collect(),
+            ),
+            Node::Delay(id, child) => NodeArena::composite(NodeKind::Delay(id), vec![NodeArena::from(*child)]),
+            Node::KeepRunningUntilFailure(child) => NodeArena::composite(NodeKind::KeepRunningUntilFailure, vec![NodeArena::from(*child)]),
+            Node::RunOnce(child) => NodeArena::composite(NodeKind::RunOnce, vec![NodeArena::from(*child)]),
+            Node::Cooldown(period, child) => NodeArena::composite(NodeKind::Cooldown(period), vec![NodeArena::from(*child)]),
+            Node::RateLimit(limit, period, child) => NodeArena::composite(NodeKind::RateLimit(limit, period), vec![NodeArena::from(*child)]),
+            Node::Switch(id, children) => NodeArena::composite(
+                NodeKind::Switch(id),
+                children.into_iter().map(NodeArena::from).collect(),
+            ),
+            Node::WeightedChoice(children) => NodeArena::composite(
+                NodeKind::WeightedChoice,
+                children.into_iter().map(|(weight, child)| {
+                    let mut arena = NodeArena::from(child);
+                    arena.set_weight(weight as f64);
+                    arena
+                }).collect(),
+            ),
+        }
+    }
+}
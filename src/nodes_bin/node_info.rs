@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::nodes_bin::{node_arena::{NodeArena, NodeIndex}, node_map::NodeIdToProcessHandleMap};
+
+// Description of a node as shown to users of the debugger, tree events, exports and statistics
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct NodeInfo {
+    pub id: Option<String>, // None for composite nodes
+    pub name: String,
+    pub description: Option<String>,
+    pub metadata: BTreeMap<String, String>,
+}
+
+// Name, description and metadata given to a node while building the tree
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct NodeMeta {
+    pub name: Option<String>, // Replaces the name of the executor or the kind of the node
+    pub description: Option<String>,
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl NodeInfo {
+    pub(crate) fn new(arena: &NodeArena, map: &NodeIdToProcessHandleMap, idx: NodeIndex) -> NodeInfo {
+        let id = arena.get_id(idx).cloned();
+        let meta = &arena.get(idx).meta;
+        let name = match (&meta.name, &id) {
+            (Some(name), _) => name.clone(),
+            (None, Some(id)) => map.get(id).map(|handle| handle.name()).unwrap_or_default(),
+            (None, None) => format!("{:?}", arena.kind(idx)),
+        };
+        Self { id, name, description: meta.description.clone(), metadata: meta.metadata.clone() }
+    }
+
+    // Infos of all nodes, indexed by NodeIndex
+    pub(crate) fn collect(arena: &NodeArena, map: &NodeIdToProcessHandleMap) -> Vec<NodeInfo> {
+        arena.indices().map(|idx| NodeInfo::new(arena, map, idx)).collect()
+    }
+}
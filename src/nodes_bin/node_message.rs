@@ -1,10 +1,12 @@
-use crate::nodes_bin::{node_error::NodeError, node::Node, node_status::Status};
+use crate::nodes::feedback::Progress;
+use crate::nodes_bin::{node_error::NodeError, node_arena::NodeIndex, node_status::Status};
 
 // Result of listening to the current action and all active conditions
 #[derive(Debug)]
 pub(crate) enum FutResult {
     CurrentNode(bool),
-    Condition(Node, bool),
+    Condition(NodeIndex, bool),
+    Branch(NodeIndex, usize), // A switch selected another branch
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -17,6 +19,9 @@ pub(crate) enum ChildMessage {
 #[derive(PartialEq, Debug, Clone)]
 pub(crate) enum ParentMessage {
     Status(Status),
+    Progress(Progress),
+    Branch(usize), // Child index selected by a switch
+    Stale(bool), // The input of a condition went stale, it reports this result until a new value arrives
     Poison(NodeError),
     Killed,
 }
\ No newline at end of file
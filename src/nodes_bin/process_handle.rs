@@ -1,17 +1,52 @@
+use std::{fmt, sync::{Arc, RwLock}};
+
 use anyhow::Result;
 
-use tokio::sync::broadcast::{Receiver, Sender};
+use tokio::sync::broadcast::{Receiver, Sender, error::{RecvError, TryRecvError}};
 
 use crate::nodes_bin::{
     node_error::NodeError,
     node_message::{ChildMessage, ParentMessage}, node_status::Status,
 };
 
+// Name the process logs with. The handle shares it, so naming the node while building the tree renames the process
+#[derive(Clone)]
+pub(crate) struct ProcessName(Arc<RwLock<String>>);
+
+impl ProcessName {
+    pub(crate) fn new(name: impl Into<String>) -> ProcessName {
+        Self(Arc::new(RwLock::new(name.into())))
+    }
+
+    pub(crate) fn get(&self) -> String {
+        self.0.read().map(|name| name.clone()).unwrap_or_default()
+    }
+
+    fn set(&self, name: &str) {
+        if let Ok(mut current) = self.0.write() {
+            *current = name.to_string();
+        }
+    }
+}
+
+impl fmt::Debug for ProcessName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.get())
+    }
+}
+
+impl fmt::Display for ProcessName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
+
 #[derive(Debug)]
 pub struct ProcessHandle {
     tx: Sender<ChildMessage>, // This handle is held by a parent, so it can send child messages
     rx: Receiver<ParentMessage>, // The parent can receive messages from its child, so can listen to the handle for messages
-    name: String,
+    name: ProcessName,
+    constant: Option<bool>, // Result of an action that always returns the same, like Success
 }
 
 impl Clone for ProcessHandle {
@@ -20,26 +55,42 @@ impl Clone for ProcessHandle {
             rx: self.rx.resubscribe(), // An rx cannot be cloned, but it can be created by subscribing to the transmitter
             tx: self.tx.clone(),
             name: self.name.clone(),
+            constant: self.constant,
         }
     }
 }
 
 impl ProcessHandle {
-    pub(crate) fn new<T>(
+    pub(crate) fn new(
         tx: Sender<ChildMessage>,
         rx: Receiver<ParentMessage>,
-        name: T,
-    ) -> ProcessHandle
-    where
-        T: Into<String>,
-    {
+        name: ProcessName,
+    ) -> ProcessHandle {
         Self {
             tx,
             rx,
-            name: name.into(),
+            name,
+            constant: None,
         }
     }
 
+    pub(crate) fn with_constant(mut self, result: bool) -> ProcessHandle {
+        self.constant = Some(result);
+        self
+    }
+
+    pub(crate) fn name(&self) -> String {
+        self.name.get()
+    }
+
+    pub(crate) fn rename(&self, name: &str) {
+        self.name.set(name)
+    }
+
+    pub(crate) fn constant(&self) -> Option<bool> {
+        self.constant
+    }
+
     pub(crate) async fn send(&mut self, msg: ChildMessage) -> Result<(), NodeError> {
         // Fire-and-forget for normal messages
         let requires_reply = matches!(msg, ChildMessage::Kill | ChildMessage::Stop);
@@ -91,13 +142,40 @@ impl ProcessHandle {
         Ok(())
     }
 
+    // Kills the process without waiting for its acknowledgement, for where nothing can be awaited like a drop
+    pub(crate) fn kill_now(&self) {
+        let _ = self.tx.send(ChildMessage::Kill);
+    }
+
     pub(crate) async fn listen(&mut self) -> Result<ParentMessage, NodeError> {
         ProcessHandle::_listen(&mut self.rx).await
     }
 
+    // Returns the oldest unread message without waiting, or None if there is none
+    pub(crate) fn try_listen(&mut self) -> Option<ParentMessage> {
+        loop {
+            match self.rx.try_recv() {
+                Ok(msg) => return Some(msg),
+                Err(TryRecvError::Lagged(n)) => log::debug!("{} skipped {n} messages", self.name),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return None,
+            }
+        }
+    }
+
+    // Discards all unread messages
+    pub(crate) fn clear(&mut self) {
+        while self.try_listen().is_some() {}
+    }
+
+    // Skips over lagged messages, only the newest ones matter and progress reports may be sent in bursts
     async fn _listen(
         rx: &mut Receiver<ParentMessage>,
     ) -> Result<ParentMessage, NodeError> {
-        Ok(rx.recv().await?)
+        loop {
+            match rx.recv().await {
+                Err(RecvError::Lagged(n)) => log::debug!("Skipped {n} messages"),
+                res => return Ok(res?),
+            }
+        }
     }
 }
\ No newline at end of file
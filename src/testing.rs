@@ -0,0 +1,240 @@
+/*
+Mocks for testing trees outside of this crate, enabled with the "testing" feature.
+They record how the engine drives them, which can be checked with the assertion helpers on MockRecord.
+*/
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use tokio::time::{sleep, Duration};
+
+use crate::nodes::{action::Executor, condition::Evaluator};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockEvent {
+    Started,        // execute() or evaluate() is called
+    Finished(bool), // Ran to completion with this result
+    Errored,        // Ran to completion with the scripted error
+    Halted,         // Dropped by the engine before finishing, by a stop or kill
+}
+
+// Shared view on the history of a mock, stays valid after the mock is moved into a tree
+#[derive(Debug, Clone, Default)]
+pub struct MockRecord {
+    history: Arc<Mutex<Vec<MockEvent>>>,
+}
+
+impl MockRecord {
+    pub fn history(&self) -> Vec<MockEvent> {
+        self.history.lock().unwrap().clone()
+    }
+
+    pub fn calls(&self) -> usize {
+        self.count(&MockEvent::Started)
+    }
+
+    pub fn halts(&self) -> usize {
+        self.count(&MockEvent::Halted)
+    }
+
+    pub fn clear(&self) {
+        self.history.lock().unwrap().clear();
+    }
+
+    #[track_caller]
+    pub fn assert_calls(&self, calls: usize) {
+        assert_eq!(self.calls(), calls, "Unexpected number of calls, history: {:?}", self.history());
+    }
+
+    #[track_caller]
+    pub fn assert_not_called(&self) {
+        self.assert_calls(0)
+    }
+
+    #[track_caller]
+    pub fn assert_halted(&self) {
+        assert!(self.halts() > 0, "Expected a halt, history: {:?}", self.history());
+    }
+
+    #[track_caller]
+    pub fn assert_history(&self, expected: &[MockEvent]) {
+        assert_eq!(self.history(), expected);
+    }
+
+    // Result of the last finished call, if any
+    pub fn last_result(&self) -> Option<bool> {
+        self.history.lock().unwrap().iter().rev().find_map(|event| match event {
+            MockEvent::Finished(res) => Some(*res),
+            _ => None,
+        })
+    }
+
+    fn count(&self, event: &MockEvent) -> usize {
+        self.history.lock().unwrap().iter().filter(|e| *e == event).count()
+    }
+
+    fn push(&self, event: MockEvent) {
+        self.history.lock().unwrap().push(event);
+    }
+}
+
+// Script shared by both mocks: every call takes the next delay and result, the last ones repeat
+#[derive(Debug, Clone)]
+struct Script {
+    results: Vec<bool>,
+    delays: Vec<Duration>,
+    error: Option<String>,
+    record: MockRecord,
+}
+
+impl Script {
+    fn new(result: bool) -> Script {
+        Self {
+            results: vec![result],
+            delays: vec![Duration::ZERO],
+            error: None,
+            record: MockRecord::default(),
+        }
+    }
+
+    async fn call(&self) -> Result<bool> {
+        let call = self.record.calls();
+        self.record.push(MockEvent::Started);
+        let guard = HaltGuard { record: &self.record, finished: false };
+
+        sleep(nth_or_last(&self.delays, call).unwrap_or_default()).await;
+        let res = match &self.error {
+            Some(msg) => Err(anyhow!("{msg}")),
+            None => Ok(nth_or_last(&self.results, call).unwrap_or(true)),
+        };
+        guard.finish(&res);
+        res
+    }
+}
+
+fn nth_or_last<T: Copy>(items: &[T], n: usize) -> Option<T> {
+    items.get(n).or(items.last()).copied()
+}
+
+// Records a halt if the call is dropped before it finishes
+struct HaltGuard<'a> {
+    record: &'a MockRecord,
+    finished: bool,
+}
+
+impl HaltGuard<'_> {
+    fn finish(mut self, res: &Result<bool>) {
+        self.finished = true;
+        self.record.push(match res {
+            Ok(res) => MockEvent::Finished(*res),
+            Err(_) => MockEvent::Errored,
+        });
+    }
+}
+
+impl Drop for HaltGuard<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.record.push(MockEvent::Halted);
+        }
+    }
+}
+
+// Succeeds immediately unless configured otherwise. Use it like any executor, e.g. BT::action(mock)
+pub struct MockAction {
+    name: String,
+    script: Script,
+}
+
+impl MockAction {
+    pub fn new(name: impl Into<String>) -> MockAction {
+        Self {
+            name: name.into(),
+            script: Script::new(true),
+        }
+    }
+
+    pub fn results(mut self, results: impl IntoIterator<Item = bool>) -> Self {
+        self.script.results = results.into_iter().collect();
+        self
+    }
+
+    pub fn delay(self, delay: Duration) -> Self {
+        self.delays([delay])
+    }
+
+    pub fn delays(mut self, delays: impl IntoIterator<Item = Duration>) -> Self {
+        self.script.delays = delays.into_iter().collect();
+        self
+    }
+
+    // Every call returns this error after its delay
+    pub fn error(mut self, msg: impl Into<String>) -> Self {
+        self.script.error = Some(msg.into());
+        self
+    }
+
+    pub fn record(&self) -> MockRecord {
+        self.script.record.clone()
+    }
+}
+
+impl Executor for MockAction {
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn execute(&mut self) -> Result<bool> {
+        self.script.call().await
+    }
+}
+
+// Ignores the value of its handle and returns the scripted results instead, the handle only triggers evaluations
+#[derive(Clone)]
+pub struct MockCondition {
+    name: String,
+    script: Script,
+}
+
+impl MockCondition {
+    pub fn new(name: impl Into<String>) -> MockCondition {
+        Self {
+            name: name.into(),
+            script: Script::new(true),
+        }
+    }
+
+    pub fn results(mut self, results: impl IntoIterator<Item = bool>) -> Self {
+        self.script.results = results.into_iter().collect();
+        self
+    }
+
+    pub fn delay(self, delay: Duration) -> Self {
+        self.delays([delay])
+    }
+
+    pub fn delays(mut self, delays: impl IntoIterator<Item = Duration>) -> Self {
+        self.script.delays = delays.into_iter().collect();
+        self
+    }
+
+    pub fn error(mut self, msg: impl Into<String>) -> Self {
+        self.script.error = Some(msg.into());
+        self
+    }
+
+    pub fn record(&self) -> MockRecord {
+        self.script.record.clone()
+    }
+}
+
+impl<V: Debug + Send + 'static> Evaluator<V> for MockCondition {
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn evaluate(&mut self, _val: V) -> Result<bool> {
+        self.script.call().await
+    }
+}
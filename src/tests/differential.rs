@@ -0,0 +1,219 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use actify::Handle;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::{Action, BT, Condition, Failure, Success, bt::Ready, execution::engine_factory::Engines, nodes_bin::{node::Node, node_map::NodeIdToProcessHandleMap}, testing::MockAction};
+use crate::tests::simulation::{Simulation, Transition};
+
+// Random tree with everything it needs to run: the scripted outcomes of the actions and the flips of the conditions
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Spec {
+    Action(Vec<(u64, bool)>),           // (delay in ms, result) per execution, the last one repeats
+    Condition { initial: bool, flips: Vec<u64> }, // Times in ms at which the value of its handle toggles
+    Constant(bool), // Success or Failure
+    Sequence(Vec<Spec>),
+    Fallback(Vec<Spec>),
+    SequenceWithMemory(Vec<Spec>),
+    FallbackWithMemory(Vec<Spec>),
+    RandomSequence(Vec<Spec>),
+    RandomFallback(Vec<Spec>),
+}
+
+// Action delays are multiples of 10 ms and flips happen at 5 ms past, so no flip coincides with a
+// finishing action and the order of events never depends on scheduling
+pub(crate) struct Generator {
+    rng: StdRng,
+    flip_times: HashSet<u64>,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Generator {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            flip_times: HashSet::new(),
+        }
+    }
+
+    pub fn tree(&mut self, depth: usize) -> Spec {
+        if depth == 0 || self.rng.random_bool(0.3) {
+            return self.leaf();
+        }
+        let children = (0..self.rng.random_range(1..=3)).map(|_| self.tree(depth - 1)).collect();
+        match self.rng.random_range(0..6) {
+            0 => Spec::Sequence(children),
+            1 => Spec::Fallback(children),
+            2 => Spec::SequenceWithMemory(children),
+            3 => Spec::FallbackWithMemory(children),
+            4 => Spec::RandomSequence(children),
+            _ => Spec::RandomFallback(children),
+        }
+    }
+
+    fn leaf(&mut self) -> Spec {
+        if self.rng.random_bool(0.15) {
+            return Spec::Constant(self.rng.random_bool(0.5));
+        }
+        if self.rng.random_bool(0.6) {
+            let outcomes = (0..self.rng.random_range(1..=2))
+                .map(|_| (10 * self.rng.random_range(1..=5), self.rng.random_bool(0.5)))
+                .collect();
+            return Spec::Action(outcomes);
+        }
+
+        let mut flips = vec![];
+        for _ in 0..self.rng.random_range(0..=2) {
+            let time = 10 * self.rng.random_range(0..30) + 5;
+            if self.flip_times.insert(time) {
+                flips.push(time);
+            }
+        }
+        flips.sort();
+        Spec::Condition { initial: self.rng.random_bool(0.5), flips }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Run {
+    pub result: bool,
+    pub transitions: Vec<Transition>,
+}
+
+// Runs the spec under simulated time, the nodes are named a<n> and c<n> in depth first order.
+// The random composites use a fixed seed, so both engines draw the same orders
+pub(crate) async fn run_spec(spec: &Spec, engine: Engines) -> Run {
+    run_spec_with(spec, engine, |bt| bt).await
+}
+
+// Runs the spec after the tree is optimized
+pub(crate) async fn run_optimized(spec: &Spec, engine: Engines) -> Run {
+    run_spec_with(spec, engine, BT::optimize).await
+}
+
+async fn run_spec_with(spec: &Spec, engine: Engines, prepare: impl FnOnce(BT<Ready>) -> BT<Ready>) -> Run {
+    let mut map = HashMap::new();
+    let mut flips = vec![];
+    let root = build(spec, &mut map, &mut flips);
+    let sim = flips
+        .into_iter()
+        .fold(Simulation::new(), |sim, (at, handle, value)| sim.set(Duration::from_millis(at), &handle, value));
+
+    let bt: BT<Ready> = BT::new().test_insert_map(map).test_root(root).set_engine(engine).seed(0);
+    let res = sim.run(prepare(bt)).await;
+    Run {
+        result: res.result,
+        transitions: res.transitions,
+    }
+}
+
+fn build(spec: &Spec, map: &mut NodeIdToProcessHandleMap, flips: &mut Vec<(u64, Handle<bool>, bool)>) -> Node {
+    let id = format!("{}", map.len());
+    match spec {
+        Spec::Action(outcomes) => {
+            let id = format!("a{id}");
+            let mock = MockAction::new(id.clone())
+                .delays(outcomes.iter().map(|(delay, _)| Duration::from_millis(*delay)))
+                .results(outcomes.iter().map(|(_, res)| *res));
+            map.insert(id.clone(), Action::new(mock));
+            Node::Action(id)
+        }
+        Spec::Condition { initial, flips: times } => {
+            let id = format!("c{id}");
+            let handle = Handle::new(*initial);
+            let mut value = *initial;
+            for at in times {
+                value = !value;
+                flips.push((*at, handle.clone(), value));
+            }
+            map.insert(id.clone(), Condition::new(id.clone(), handle, |x| x));
+            Node::Condition(id)
+        }
+        Spec::Constant(result) => {
+            let id = format!("k{id}");
+            map.insert(id.clone(), if *result { Success::new() } else { Failure::new() });
+            Node::Action(id)
+        }
+        Spec::Sequence(children) => Node::Sequence(children.iter().map(|c| build(c, map, flips)).collect()),
+        Spec::Fallback(children) => Node::Fallback(children.iter().map(|c| build(c, map, flips)).collect()),
+        Spec::SequenceWithMemory(children) => Node::SequenceWithMemory(children.iter().map(|c| build(c, map, flips)).collect()),
+        Spec::FallbackWithMemory(children) => Node::FallbackWithMemory(children.iter().map(|c| build(c, map, flips)).collect()),
+        Spec::RandomSequence(children) => Node::RandomSequence(children.iter().map(|c| build(c, map, flips)).collect()),
+        Spec::RandomFallback(children) => Node::RandomFallback(children.iter().map(|c| build(c, map, flips)).collect()),
+    }
+}
+
+// The runs of both engines, if they disagree
+pub(crate) async fn compare_engines(spec: &Spec) -> Option<(Run, Run)> {
+    let static_run = run_spec(spec, Engines::Static).await;
+    let dynamic_run = run_spec(spec, Engines::Dynamic).await;
+    (static_run != dynamic_run).then_some((static_run, dynamic_run))
+}
+
+// Greedily applies the first simplification that keeps the spec failing, until none does
+pub(crate) async fn shrink(mut spec: Spec, fails: impl AsyncFn(&Spec) -> bool) -> Spec {
+    'outer: loop {
+        for candidate in simplifications(&spec) {
+            if fails(&candidate).await {
+                spec = candidate;
+                continue 'outer;
+            }
+        }
+        return spec;
+    }
+}
+
+// Strictly smaller variants of the spec, the biggest steps first
+fn simplifications(spec: &Spec) -> Vec<Spec> {
+    let mut candidates = vec![];
+    match spec {
+        Spec::Action(outcomes) => {
+            if outcomes.len() > 1 {
+                for i in 0..outcomes.len() {
+                    let mut outcomes = outcomes.clone();
+                    outcomes.remove(i);
+                    candidates.push(Spec::Action(outcomes));
+                }
+            }
+            if outcomes.iter().any(|(delay, _)| *delay > 10) {
+                candidates.push(Spec::Action(outcomes.iter().map(|(_, res)| (10, *res)).collect()));
+            }
+        }
+        Spec::Constant(_) => (),
+        Spec::Condition { initial, flips } => {
+            for i in 0..flips.len() {
+                let mut flips = flips.clone();
+                flips.remove(i);
+                candidates.push(Spec::Condition { initial: *initial, flips });
+            }
+        }
+        Spec::Sequence(children) | Spec::Fallback(children) |
+        Spec::SequenceWithMemory(children) | Spec::FallbackWithMemory(children) |
+        Spec::RandomSequence(children) | Spec::RandomFallback(children) => {
+            let rebuild = |children: Vec<Spec>| match spec {
+                Spec::Sequence(_) => Spec::Sequence(children),
+                Spec::Fallback(_) => Spec::Fallback(children),
+                Spec::SequenceWithMemory(_) => Spec::SequenceWithMemory(children),
+                Spec::FallbackWithMemory(_) => Spec::FallbackWithMemory(children),
+                Spec::RandomSequence(_) => Spec::RandomSequence(children),
+                _ => Spec::RandomFallback(children),
+            };
+            candidates.extend(children.iter().cloned());
+            if children.len() > 1 {
+                for i in 0..children.len() {
+                    let mut children = children.clone();
+                    children.remove(i);
+                    candidates.push(rebuild(children));
+                }
+            }
+            for (i, child) in children.iter().enumerate() {
+                for simpler in simplifications(child) {
+                    let mut children = children.clone();
+                    children[i] = simpler;
+                    candidates.push(rebuild(children));
+                }
+            }
+        }
+    }
+    candidates
+}
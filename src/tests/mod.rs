@@ -1,4 +1,27 @@
+mod differential;
+mod simulation;
+mod test_arena;
 mod test_conversion;
 mod test_traversal;
 mod test_execution;
-mod test_rust_api;
\ No newline at end of file
+mod test_rust_api;
+mod test_tick;
+mod test_debugger;
+mod test_simulation;
+mod test_testing;
+mod test_differential;
+mod test_feedback;
+mod test_blocking;
+mod test_condition_options;
+mod test_memory;
+mod test_random;
+mod test_utility;
+mod test_switch;
+mod test_decorators;
+mod test_delay;
+mod test_dsl;
+mod test_macros;
+mod test_derive;
+mod test_names;
+mod test_analyzer;
+mod test_optimizer;
\ No newline at end of file
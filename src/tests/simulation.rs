@@ -0,0 +1,104 @@
+use std::{fmt::Debug, future::Future, pin::Pin, time::Duration};
+
+use actify::Handle;
+use tokio::{sync::broadcast::error::TryRecvError, time::{Instant, sleep_until}};
+
+use crate::{BT, Status, bt::Ready, execution::engine_factory::Engines};
+
+// Engines the tests run their trees on, the tick engine only where it supports the tested behavior
+pub(crate) const ENGINES: [Engines; 3] = [Engines::Static, Engines::Dynamic, Engines::Tick(Duration::from_millis(10))];
+pub(crate) const EVENT_ENGINES: [Engines; 2] = [Engines::Static, Engines::Dynamic];
+
+pub(crate) fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+type Step = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+// Runs a tree under paused tokio time: sleeps complete instantly once all tasks are idle, so the
+// same script always gives the same transitions at the same (virtual) times.
+// Use it from #[tokio::test(start_paused = true)], pausing later leaves the clock off the timer's
+// millisecond grid and every sleep then takes an extra millisecond
+pub(crate) struct Simulation {
+    timeline: Vec<(Duration, Step)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Transition {
+    pub at: Duration,
+    pub name: String,
+    pub status: Status,
+}
+
+pub(crate) struct SimulationResult {
+    pub result: bool,
+    pub transitions: Vec<Transition>,
+}
+
+impl SimulationResult {
+    // Transitions as (millis, name, status), which is convenient to compare against
+    pub fn timeline(&self) -> Vec<(u128, &str, Status)> {
+        self.transitions
+            .iter()
+            .map(|t| (t.at.as_millis(), t.name.as_str(), t.status))
+            .collect()
+    }
+}
+
+impl Simulation {
+    pub fn new() -> Simulation {
+        Self { timeline: vec![] }
+    }
+
+    pub fn set<V>(mut self, at: Duration, handle: &Handle<V>, value: V) -> Simulation
+    where
+        V: Clone + Debug + Send + Sync + 'static,
+    {
+        let handle = handle.clone();
+        self.timeline.push((at, Box::pin(async move { handle.set(value).await })));
+        self
+    }
+
+    pub async fn run(mut self, bt: BT<Ready>) -> SimulationResult {
+        let mut events = bt.subscribe();
+        let start = Instant::now();
+
+        self.timeline.sort_by_key(|(at, _)| *at);
+        let timeline = async move {
+            for (at, step) in self.timeline {
+                sleep_until(start + at).await;
+                step.await;
+            }
+        };
+
+        let run = bt.run();
+        tokio::pin!(run);
+        let bt = tokio::select! {
+            bt = &mut run => bt,
+            _ = timeline => run.await,
+        };
+
+        // A lagged receiver lost events, the timeline would be cut short without it showing
+        let mut transitions = vec![];
+        loop {
+            let event = match events.try_recv() {
+                Ok(event) => event,
+                Err(TryRecvError::Lagged(n)) => panic!("The simulation missed {n} tree events, raise EVENT_CHANNEL_SIZE"),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            };
+            if event.progress.is_some() || event.stale {
+                continue; // Not a transition, a stale condition that flips reports the flip as well
+            }
+            transitions.push(Transition {
+                at: event.time - start,
+                name: event.node.name,
+                status: event.status,
+            });
+        }
+
+        SimulationResult {
+            result: bt.result(),
+            transitions,
+        }
+    }
+}
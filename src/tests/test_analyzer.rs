@@ -0,0 +1,73 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use actify::Handle;
+    use crate::{BT, Builder, Finding, NodeInfo, testing::MockAction, nodes::condition::ClosureEvaluator};
+
+    fn action(name: &str) -> BT<Builder> {
+        BT::action(MockAction::new(name))
+    }
+
+    fn analyze(tree: BT<Builder>) -> Vec<Finding> {
+        BT::new().root(tree).analyze()
+    }
+
+    fn name(info: &NodeInfo) -> &str {
+        &info.name
+    }
+
+    #[tokio::test]
+    async fn test_analyze_clean_tree() {
+        let handle = Handle::new(true);
+        let cond = BT::condition(handle, ClosureEvaluator::new("ok".into(), |x: bool| x));
+        let tree = BT::fb(vec![BT::seq(vec![cond, action("a")]), action("b")]);
+        assert_eq!(analyze(tree), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_dead_fallback_children() {
+        let tree = BT::fb(vec![action("a"), BT::success(), action("b"), action("c")]);
+        let findings = analyze(tree);
+
+        assert_eq!(findings.len(), 3);
+        assert!(matches!(&findings[0], Finding::DeadChildren { fallback, child } if name(fallback) == "Fallback" && name(child) == "SUCCESS"));
+        assert!(matches!(&findings[1], Finding::Unreachable(node) if name(node) == "b"));
+        assert!(matches!(&findings[2], Finding::Unreachable(node) if name(node) == "c"));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_sequence_with_failure() {
+        // The nested fallback always fails, which decides the outer sequence
+        let tree = BT::seq(vec![action("a"), BT::fb(vec![BT::failure(), BT::failure()]).name("fails"), action("b")]);
+        let findings = analyze(tree);
+
+        assert_eq!(findings.len(), 2);
+        assert!(matches!(&findings[0], Finding::AlwaysFails { sequence, child } if name(sequence) == "Sequence" && name(child) == "fails"));
+        assert!(matches!(&findings[1], Finding::Unreachable(node) if name(node) == "b"));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_empty_and_flattenable() {
+        let tree = BT::seq(vec![
+            BT::seq(vec![action("a"), action("b")]),
+            BT::seq(vec![action("c")]).name("named"),
+            BT::fb(vec![]),
+        ]);
+        let findings = analyze(tree);
+
+        assert_eq!(findings.len(), 2);
+        assert!(matches!(&findings[0], Finding::Flattenable { parent, child } if name(parent) == "Sequence" && name(child) == "Sequence"));
+        assert!(matches!(&findings[1], Finding::EmptyComposite(node) if name(node) == "Fallback"));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_unreachable_subtree() {
+        // Only the topmost node of the unreachable subtree is reported
+        let tree = BT::seq(vec![BT::failure(), BT::fb(vec![action("a"), action("b")])]);
+        let findings = analyze(tree);
+
+        assert_eq!(findings.len(), 2);
+        assert!(matches!(&findings[0], Finding::AlwaysFails { child, .. } if name(child) == "FAILURE"));
+        assert!(matches!(&findings[1], Finding::Unreachable(node) if name(node) == "Fallback"));
+    }
+}
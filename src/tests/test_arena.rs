@@ -0,0 +1,51 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use crate::nodes_bin::node::Node;
+    use crate::nodes_bin::node_arena::{NodeArena, NodeKind};
+
+    #[test]
+    fn test_arena_roundtrip() {
+        let root = Node::Fallback(vec![
+            Node::Sequence(vec![
+                Node::Condition("c1".into()),
+                Node::Action("a1".into()),
+            ]),
+            Node::Action("a2".into()),
+        ]);
+
+        let arena = NodeArena::from(root.clone());
+
+        assert_eq!(arena.to_node(arena.root()), root);
+    }
+
+    #[test]
+    fn test_arena_parent_and_siblings() {
+        let arena = NodeArena::from(Node::Fallback(vec![
+            Node::Sequence(vec![
+                Node::Condition("c1".into()),
+                Node::Action("a1".into()),
+            ]),
+            Node::Action("a2".into()),
+        ]));
+
+        let root = arena.root();
+        let seq = arena.children(root)[0];
+        let a2 = arena.children(root)[1];
+        let c1 = arena.children(seq)[0];
+        let a1 = arena.children(seq)[1];
+
+        assert_eq!(arena.kind(seq), &NodeKind::Sequence);
+        assert_eq!(arena.get_id(a1), Some(&"a1".to_string()));
+        assert_eq!(arena.parent(c1), Some(seq));
+        assert_eq!(arena.parent(seq), Some(root));
+        assert_eq!(arena.parent(root), None);
+
+        assert_eq!(arena.next_sibling(c1), Some(a1));
+        assert_eq!(arena.next_sibling(a1), None);
+        assert_eq!(arena.next_sibling(seq), Some(a2));
+        assert_eq!(arena.next_sibling(root), None);
+
+        assert_eq!(arena.trace(a1), vec![root, seq, a1]);
+    }
+}
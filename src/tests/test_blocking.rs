@@ -0,0 +1,92 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+    use actify::Handle;
+    use anyhow::Result;
+    use tokio::time::{Instant, sleep};
+    use crate::{Action, BT, BlockingExecutor, CancelFlag, nodes::condition::ClosureEvaluator, nodes_bin::node::Node};
+
+    // Blocks its thread in steps of 10 ms
+    struct Crunch {
+        steps: usize,
+        cancelled: Arc<AtomicBool>,
+    }
+
+    impl Crunch {
+        fn new(steps: usize) -> (Crunch, Arc<AtomicBool>) {
+            let cancelled = Arc::new(AtomicBool::new(false));
+            (Self { steps, cancelled: cancelled.clone() }, cancelled)
+        }
+    }
+
+    impl BlockingExecutor for Crunch {
+        fn get_name(&self) -> String {
+            "crunch".to_string()
+        }
+
+        fn execute(&mut self, cancel: &CancelFlag) -> Result<bool> {
+            for _ in 0..self.steps {
+                if cancel.is_cancelled() {
+                    self.cancelled.store(true, Ordering::SeqCst);
+                    return Ok(false);
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocking_action_succeeds() {
+        let (crunch, cancelled) = Crunch::new(5);
+        let bt = BT::new().root(BT::seq(vec![BT::action_blocking(crunch)])).run().await;
+
+        assert_eq!(bt.result(), true);
+        assert_eq!(cancelled.load(Ordering::SeqCst), false);
+    }
+
+    #[tokio::test]
+    async fn test_blocking_action_does_not_block_runtime() {
+        // The test runtime has a single thread, so the condition can only interrupt if the crunching runs elsewhere
+        let (crunch, cancelled) = Crunch::new(200);
+        let handle = Handle::new(true);
+        let bt = BT::new().root(BT::seq(vec![
+            BT::condition(handle.clone(), ClosureEvaluator::new("go".into(), |x: bool| x)),
+            BT::action_blocking(crunch),
+        ]));
+
+        let start = Instant::now();
+        let (bt, _) = tokio::join!(bt.run(), async {
+            sleep(Duration::from_millis(100)).await;
+            handle.set(false).await;
+        });
+
+        assert_eq!(bt.result(), false);
+        assert!(start.elapsed() < Duration::from_millis(1000));
+
+        sleep(Duration::from_millis(50)).await; // Give the thread time to see the flag
+        assert_eq!(cancelled.load(Ordering::SeqCst), true);
+    }
+
+    #[tokio::test]
+    async fn test_blocking_action_on_dedicated_pool() {
+        let pool = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .max_blocking_threads(1)
+            .build()
+            .unwrap();
+
+        let (crunch, _) = Crunch::new(3);
+        let mut map = HashMap::new();
+        map.insert("a1".to_string(), Action::blocking_on(crunch, pool.handle().clone()));
+
+        let bt = BT::new().test_insert_map(map).test_root(Node::Action("a1".into()));
+        assert_eq!(bt.run().await.result(), true);
+
+        pool.shutdown_background(); // Dropping a runtime is not allowed in async context
+    }
+}
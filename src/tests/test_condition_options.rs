@@ -0,0 +1,244 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::collections::HashMap;
+    use actify::Handle;
+    use crate::{Action, BT, Builder, Condition, ConditionOptions, execution::engine_factory::Engines, Status, nodes::condition::ClosureEvaluator, nodes_bin::node::Node, testing::MockAction, tests::simulation::{Simulation, ms, EVENT_ENGINES}};
+
+    fn positive() -> ClosureEvaluator<i32, fn(i32) -> bool> {
+        ClosureEvaluator::new("cond".into(), |x| x > 0)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_debounce_ignores_bounces() {
+        let handle = Handle::new(1);
+        let bt = BT::new().root(BT::seq(vec![
+            BT::condition_with(handle.clone(), positive(), ConditionOptions::new().debounce(ms(50))),
+            BT::action(MockAction::new("act").delay(ms(500))),
+        ]));
+
+        let sim = Simulation::new()
+            .set(ms(100), &handle, -1)
+            .set(ms(120), &handle, 1) // Bounced back within 50 ms
+            .set(ms(300), &handle, -1)
+            .run(bt)
+            .await;
+
+        assert_eq!(sim.result, false);
+        assert_eq!(sim.timeline(), vec![
+            (0, "cond", Status::Success),
+            (0, "act", Status::Running),
+            (350, "cond", Status::Failure),
+            (350, "act", Status::Idle), // Still running when the tree finished
+        ]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_min_flip_interval_delays_flips() {
+        let handle = Handle::new(1);
+        let mut map = HashMap::new();
+        map.insert("cond".to_string(), Condition::new_with(positive(), handle.clone(), ConditionOptions::new().min_flip_interval(ms(100))));
+        map.insert("long".to_string(), Action::new(MockAction::new("long").delay(ms(500))));
+        map.insert("other".to_string(), Action::new(MockAction::new("other").delay(ms(500))));
+
+        // The condition stays monitored after it failed, so it can flip back
+        let root = Node::Fallback(vec![
+            Node::Sequence(vec![Node::Condition("cond".into()), Node::Action("long".into())]),
+            Node::Action("other".into()),
+        ]);
+        let bt = BT::new().test_insert_map(map).test_root(root);
+
+        let sim = Simulation::new()
+            .set(ms(100), &handle, -1)
+            .set(ms(150), &handle, 1)
+            .run(bt)
+            .await;
+
+        let flips: Vec<_> = sim.timeline().into_iter().filter(|(_, name, _)| *name == "cond").collect();
+        assert_eq!(flips, vec![
+            (0, "cond", Status::Success),
+            (100, "cond", Status::Failure),
+            (200, "cond", Status::Success), // Not before 100 ms after the previous flip
+            (700, "cond", Status::Idle), // Monitored until the tree finished
+        ]);
+    }
+
+    // Charged from 15 on, but only flips above 20 and below 10
+    fn charged(battery: &Handle<i32>) -> BT<Builder> {
+        let options = ConditionOptions::new().hysteresis(20, 10).unwrap();
+        BT::condition_with(battery.clone(), ClosureEvaluator::new("charged".into(), |x: i32| x >= 15), options)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_hysteresis() {
+        let battery = Handle::new(30);
+        let bt = BT::new().root(BT::seq(vec![charged(&battery), BT::action(MockAction::new("act").delay(ms(500)))]));
+
+        let sim = Simulation::new()
+            .set(ms(100), &battery, 12) // The evaluator fails, but the value is above the falling threshold
+            .set(ms(200), &battery, 5)
+            .run(bt)
+            .await;
+
+        assert_eq!(sim.result, false);
+        assert_eq!(sim.timeline(), vec![
+            (0, "charged", Status::Success),
+            (0, "act", Status::Running),
+            (200, "charged", Status::Failure),
+            (200, "act", Status::Idle),
+        ]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_hysteresis_holds_rising_flip() {
+        let battery = Handle::new(5);
+        let bt = BT::new().root(BT::fb(vec![
+            BT::seq(vec![charged(&battery), BT::action(MockAction::new("work").delay(ms(500)))]),
+            BT::action(MockAction::new("charge").delay(ms(500))),
+        ]));
+
+        let sim = Simulation::new()
+            .set(ms(100), &battery, 18) // The evaluator succeeds, but the value is below the rising threshold
+            .set(ms(200), &battery, 25)
+            .run(bt)
+            .await;
+
+        assert_eq!(sim.result, true);
+        let flips: Vec<_> = sim.timeline().into_iter().filter(|(_, name, _)| *name == "charged").collect();
+        assert_eq!(flips, vec![(0, "charged", Status::Failure), (200, "charged", Status::Success), (700, "charged", Status::Idle)]);
+    }
+
+    #[test]
+    fn test_hysteresis_rejects_inverted_thresholds() {
+        let err = ConditionOptions::new().hysteresis(10.0, 20.0).unwrap_err();
+        assert!(err.to_string().contains("rising"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_max_age_fails_stale_condition() {
+        let handle = Handle::new(1);
+        let bt = BT::new().root(BT::seq(vec![
+            BT::condition_with(handle.clone(), positive(), ConditionOptions::new().max_age(ms(100))),
+            BT::action(MockAction::new("act").delay(ms(500))),
+        ]));
+
+        let sim = Simulation::new()
+            .set(ms(50), &handle, 1)
+            .set(ms(120), &handle, 2) // The sensor dies after this value
+            .run(bt)
+            .await;
+
+        assert_eq!(sim.result, false);
+        assert_eq!(sim.timeline(), vec![
+            (0, "cond", Status::Success),
+            (0, "act", Status::Running),
+            (220, "cond", Status::Failure),
+            (220, "act", Status::Idle), // Still running when the tree finished
+        ]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_max_age_recovers_with_new_value() {
+        let handle = Handle::new(1);
+        let mut map = HashMap::new();
+        map.insert("cond".to_string(), Condition::new_with(positive(), handle.clone(), ConditionOptions::new().max_age(ms(100))));
+        map.insert("init".to_string(), Action::new(MockAction::new("init").delay(ms(150))));
+        map.insert("long".to_string(), Action::new(MockAction::new("long").delay(ms(500))));
+        map.insert("other".to_string(), Action::new(MockAction::new("other").delay(ms(500))));
+
+        let root = Node::Sequence(vec![
+            Node::Action("init".into()),
+            Node::Fallback(vec![
+                Node::Sequence(vec![Node::Condition("cond".into()), Node::Action("long".into())]),
+                Node::Action("other".into()),
+            ]),
+        ]);
+        let bt = BT::new().test_insert_map(map).test_root(root);
+
+        let sim = Simulation::new()
+            .set(ms(400), &handle, 1)
+            .run(bt)
+            .await;
+
+        let flips: Vec<_> = sim.timeline().into_iter().filter(|(_, name, _)| *name == "cond").collect();
+        assert_eq!(flips, vec![
+            (150, "cond", Status::Success), // The max age counts from the start of the condition
+            (250, "cond", Status::Failure), // No value since the start
+            (400, "cond", Status::Success),
+            (500, "cond", Status::Failure), // Stale again
+            (1000, "cond", Status::Idle), // Monitored until the tree finished
+        ]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_max_age_emits_stale_event() {
+        for engine in [Engines::Static, Engines::Dynamic, Engines::Tick(ms(10))] {
+            let handle = Handle::new(1);
+            let options = ConditionOptions::new().max_age(ms(100)).stale_result(true);
+            let bt = BT::new().set_engine(engine).root(BT::seq(vec![
+                BT::condition_with(handle.clone(), positive(), options),
+                BT::action(MockAction::new("act").delay(ms(300))),
+            ]));
+            let mut events = bt.subscribe();
+
+            let sim = Simulation::new().run(bt).await;
+            assert_eq!(sim.result, true);
+
+            // The stale result equals the status, so only the stale event shows it
+            let mut stale = vec![];
+            while let Ok(event) = events.try_recv() {
+                if event.stale {
+                    stale.push((event.node.name, event.status));
+                }
+            }
+            assert_eq!(stale, vec![("cond".to_string(), Status::Success)], "{engine:?}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_checked_once_condition_is_not_monitored() {
+        for engine in [Engines::Static, Engines::Dynamic, Engines::Tick(ms(10))] {
+            let handle = Handle::new(1);
+            let bt = BT::new().set_engine(engine).root(BT::seq(vec![
+                BT::checked_once(BT::condition(handle.clone(), positive())),
+                BT::action(MockAction::new("act").delay(ms(300))),
+            ]));
+
+            let sim = Simulation::new().set(ms(100), &handle, -1).run(bt).await;
+
+            // The tick engine only sees the action finish on its next tick, so leave out the times
+            let transitions: Vec<_> = sim.timeline().into_iter().map(|(_, name, status)| (name, status)).collect();
+            assert_eq!(sim.result, true);
+            assert_eq!(transitions, vec![
+                ("cond", Status::Success),
+                ("act", Status::Running),
+                ("act", Status::Success),
+            ]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_checked_once_sequence() {
+        for engine in EVENT_ENGINES {
+            let h1 = Handle::new(1);
+            let h2 = Handle::new(1);
+            let bt = BT::new().set_engine(engine).root(BT::seq(vec![
+                BT::checked_once(BT::seq(vec![
+                    BT::condition(h1.clone(), ClosureEvaluator::new("c1".into(), |x: i32| x > 0)),
+                    BT::condition(h2.clone(), ClosureEvaluator::new("c2".into(), |x: i32| x > 0)),
+                ])),
+                BT::condition(h2.clone(), ClosureEvaluator::new("c3".into(), |x: i32| x > 0)),
+                BT::action(MockAction::new("act").delay(ms(300))),
+            ]));
+
+            let sim = Simulation::new()
+                .set(ms(100), &h1, -1) // Ignored
+                .set(ms(200), &h2, -1) // Still interrupts through c3
+                .run(bt)
+                .await;
+
+            assert_eq!(sim.result, false);
+            assert!(sim.timeline().ends_with(&[(200, "c3", Status::Failure), (200, "act", Status::Idle)]));
+        }
+    }
+}
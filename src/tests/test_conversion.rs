@@ -7,15 +7,25 @@ mod tests {
     use std::collections::HashMap;
     use tokio::time::{Duration, sleep};
     use crate::bt::Ready;
-    use crate::execution::static_engine::converter::convert_bt;
+    use crate::execution::static_engine::converter::{BehaviorTreeMap, convert_bt};
+    use crate::nodes::condition::ClosureEvaluator;
     use crate::execution::traversal::{search_next, search_start};
-    use crate::nodes::action::mocking::MockAction;
+    use crate::testing::MockAction;
     use crate::nodes_bin::node::Node;
     use crate::nodes_bin::process_handle::ProcessHandle;
     use crate::nodes_bin::node_status::Status;
-    use crate::{BT, Condition, Failure, Success, Wait};
+    use crate::{Action, BT, Condition, Failure, Success, Wait};
     use logtest::Logger;
 
+    // Translates the indices in the map back into nodes, so they can be compared by value
+    fn to_node_map(bt: &BT<Ready>, map: BehaviorTreeMap) -> HashMap<(Node, Status), Option<Node>> {
+        map.into_iter()
+            .map(|((node, status), next)| {
+                ((bt.arena.to_node(node), status), next.map(|n| bt.arena.to_node(n)))
+            })
+            .collect()
+    }
+
     #[tokio::test]
     async fn test_convert_simple_action_root() {
         let mut map = HashMap::new();
@@ -29,8 +39,8 @@ mod tests {
             .test_root(root.clone())
             .name("test_tree");
 
-        let mut bt: BT<Ready> = bt.test_into_state();
-        let map = convert_bt(&mut bt);
+        let bt: BT<Ready> = bt.test_into_state();
+        let map = to_node_map(&bt, convert_bt(&bt));
 
         assert_eq!(map.len(), 2);
 
@@ -58,8 +68,8 @@ mod tests {
             .test_root(root)
             .name("test_tree");
 
-        let mut bt: BT<Ready> = bt.test_into_state();
-        let map = convert_bt(&mut bt);
+        let bt: BT<Ready> = bt.test_into_state();
+        let map = to_node_map(&bt, convert_bt(&bt));
 
         // cond SUCCESS → action
         assert_eq!(
@@ -86,8 +96,8 @@ mod tests {
     async fn test_fallback_cond_then_action_and_action2() {
         let mut map = HashMap::new();
         let cond = Condition::new("cond1", Handle::new(1), |x| x > 0);
-        let a1 = MockAction::new(1);
-        let a2 = MockAction::new(2);
+        let a1 = Action::new(MockAction::new("1").delay(Duration::from_millis(500)));
+        let a2 = Action::new(MockAction::new("2").delay(Duration::from_millis(500)));
         let id1 = "c1".to_string();
         let id2 = "a1".to_string();
         let id3 = "a2".to_string();
@@ -106,8 +116,8 @@ mod tests {
             .test_insert_map(map)
             .test_root(root)
             .name("test_tree");
-        let mut bt: BT<Ready> = bt.test_into_state();
-        let map = convert_bt(&mut bt);
+        let bt: BT<Ready> = bt.test_into_state();
+        let map = to_node_map(&bt, convert_bt(&bt));
         // Fallback logic:
         // Cond SUCCESS → A1
         assert_eq!(
@@ -139,7 +149,7 @@ mod tests {
         let mut map = HashMap::new();
         let cond1 = Condition::new("c1", Handle::new(1), |x| x > 0);
         let cond2 = Condition::new("c2", Handle::new(2), |x| x > 5);
-        let act = MockAction::new(1);
+        let act = Action::new(MockAction::new("1").delay(Duration::from_millis(500)));
         let id1 = "c1".to_string();
         let id2 = "c2".to_string();
         let id3 = "a1".to_string();
@@ -155,8 +165,8 @@ mod tests {
             .test_insert_map(map)
             .test_root(root)
             .name("test_tree");
-        let mut bt: BT<Ready> = bt.test_into_state();
-        let map = convert_bt(&mut bt);
+        let bt: BT<Ready> = bt.test_into_state();
+        let map = to_node_map(&bt, convert_bt(&bt));
         // cond1 SUCCESS → cond2
         assert_eq!(
             map.get(&(Node::Condition(id1.clone()), Status::Success)),
@@ -185,10 +195,10 @@ mod tests {
     async fn test_multiple_paths_and_selectors() {
         let mut map = HashMap::new();
         let cond1 = Condition::new("c1", Handle::new(1), |x| x > 0);
-        let a1 = MockAction::new(1);
+        let a1 = Action::new(MockAction::new("1").delay(Duration::from_millis(500)));
         let cond2 = Condition::new("c2", Handle::new(2), |x| x > 10);
-        let a2 = MockAction::new(2);
-        let a3 = MockAction::new(3);
+        let a2 = Action::new(MockAction::new("2").delay(Duration::from_millis(500)));
+        let a3 = Action::new(MockAction::new("3").delay(Duration::from_millis(500)));
         let id1 = "c1".to_string();
         let id2 = "a1".to_string();
         let id3 = "c2".to_string();
@@ -214,8 +224,8 @@ mod tests {
             .test_insert_map(map)
             .test_root(root)
             .name("test_tree");
-        let mut bt: BT<Ready> = bt.test_into_state();
-        let map = convert_bt(&mut bt);
+        let bt: BT<Ready> = bt.test_into_state();
+        let map = to_node_map(&bt, convert_bt(&bt));
         // cond1 SUCCESS → a1
         assert_eq!(
             map.get(&(Node::Condition(id1.clone()), Status::Success)),
@@ -254,4 +264,34 @@ mod tests {
         }
     }
 
-}
\ No newline at end of file
+    #[tokio::test]
+    async fn test_checked_once_conditions_are_not_reactive() {
+        let handle = Handle::new(1);
+        let bt = BT::new().root(BT::seq(vec![
+            BT::checked_once(BT::condition(handle.clone(), ClosureEvaluator::new("once".into(), |x: i32| x > 0))),
+            BT::condition(handle.clone(), ClosureEvaluator::new("reactive".into(), |x: i32| x > 0)),
+            BT::action(MockAction::new("act")),
+        ]));
+
+        let reactive: Vec<_> = bt.arena.indices()
+            .filter(|idx| bt.arena.is_reactive(*idx))
+            .map(|idx| bt.map[bt.arena.get_id(idx).unwrap()].name().to_string())
+            .collect();
+        assert_eq!(reactive, vec!["reactive"]);
+    }
+
+    #[tokio::test]
+    async fn test_checked_once_conditions_keep_their_transitions() {
+        let tree = |once: bool| {
+            let cond = BT::condition(Handle::new(1), ClosureEvaluator::new("cond".into(), |x: i32| x > 0));
+            let cond = if once { BT::checked_once(cond) } else { cond };
+            BT::new().root(BT::fb(vec![
+                BT::seq(vec![cond, BT::action(MockAction::new("act"))]),
+                BT::action(MockAction::new("other")),
+            ]))
+        };
+
+        // Same arena layout, so the indices can be compared directly
+        assert_eq!(convert_bt(&tree(true)), convert_bt(&tree(false)));
+    }
+}
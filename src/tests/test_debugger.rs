@@ -0,0 +1,99 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+    use actify::Handle;
+    use tokio::time::sleep;
+    use crate::{Action, BT, Breakpoint, Condition, NodeInfo, PausePoint, Success, bt::Ready, execution::engine_factory::Engines, testing::MockAction, nodes_bin::node::Node};
+
+    fn node_info(id: &str, name: &str) -> NodeInfo {
+        NodeInfo { id: Some(id.to_string()), name: name.to_string(), ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn test_debugger_step_through() {
+        let mut map = HashMap::new();
+        map.insert("a1".to_string(), Success::new());
+        map.insert("a2".to_string(), Success::new());
+
+        let seq = Node::Sequence(vec![Node::Action("a1".into()), Node::Action("a2".into())]);
+        let mut bt: BT<Ready> = BT::new().test_insert_map(map).test_root(seq).name("test_tree");
+        let mut debugger = bt.debugger();
+
+        let run = tokio::spawn(bt.run());
+
+        let state = debugger.wait_paused().await.unwrap();
+        assert_eq!(state.paused_at, PausePoint::Start(node_info("a1", "SUCCESS")));
+        assert_eq!(state.trace, vec![
+            NodeInfo { id: None, name: "Sequence".to_string(), ..Default::default() },
+            node_info("a1", "SUCCESS"),
+        ]);
+        debugger.step().await;
+
+        let state = debugger.wait_paused().await.unwrap();
+        assert_eq!(state.paused_at, PausePoint::Start(node_info("a2", "SUCCESS")));
+        debugger.step().await;
+
+        assert_eq!(debugger.wait_paused().await, None);
+        assert_eq!(run.await.unwrap().result(), true);
+    }
+
+    #[tokio::test]
+    async fn test_debugger_breakpoint_on_id() {
+        let mut map = HashMap::new();
+        map.insert("a1".to_string(), Success::new());
+        map.insert("a2".to_string(), Success::new());
+        map.insert("a3".to_string(), Success::new());
+
+        let seq = Node::Sequence(vec![
+            Node::Action("a1".into()),
+            Node::Action("a2".into()),
+            Node::Action("a3".into()),
+        ]);
+        let mut bt: BT<Ready> = BT::new().test_insert_map(map).test_root(seq).set_engine(Engines::Static).name("test_tree");
+        let mut debugger = bt.debugger();
+        debugger.add_breakpoint(Breakpoint::Id("a3".to_string()));
+
+        let (bt, _) = tokio::join!(
+            bt.run(),
+            async {
+                debugger.resume().await;
+                let state = debugger.wait_paused().await.unwrap();
+                assert_eq!(state.pause, 2);
+                assert_eq!(state.paused_at, PausePoint::Start(node_info("a3", "SUCCESS")));
+                debugger.resume().await;
+            }
+        );
+
+        assert_eq!(bt.result(), true);
+    }
+
+    #[tokio::test]
+    async fn test_debugger_pauses_on_condition_trigger() {
+        let mut map = HashMap::new();
+        let handle = Handle::new(1);
+        map.insert("cond".to_string(), Condition::new("cond", handle.clone(), |x| x > 0));
+        map.insert("action".to_string(), Action::new(MockAction::new("1").delay(Duration::from_millis(500))));
+
+        let seq = Node::Sequence(vec![Node::Condition("cond".into()), Node::Action("action".into())]);
+        let mut bt: BT<Ready> = BT::new().test_insert_map(map).test_root(seq).name("test_tree");
+        let mut debugger = bt.debugger();
+        debugger.add_breakpoint(Breakpoint::Name("cond".to_string()));
+
+        let (bt, _) = tokio::join!(
+            bt.run(),
+            async {
+                debugger.resume().await; // Pass the start of the condition
+                sleep(Duration::from_millis(100)).await;
+                handle.set(-1).await;
+
+                let state = debugger.wait_paused().await.unwrap();
+                assert_eq!(state.paused_at, PausePoint::ConditionTrigger(node_info("cond", "cond"), false));
+                assert_eq!(state.active_conditions, vec![node_info("cond", "cond")]);
+                debugger.resume().await;
+            }
+        );
+
+        assert_eq!(bt.result(), false);
+    }
+}
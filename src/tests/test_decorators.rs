@@ -0,0 +1,208 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use actify::Handle;
+    use tokio::time::advance;
+    use crate::{BT, Builder, Condition, Status, bt::Ready, execution::engine_factory::Engines};
+    use crate::{testing::{MockAction, MockRecord}, tests::simulation::{Simulation, ms, ENGINES, EVENT_ENGINES}, nodes::condition::ClosureEvaluator};
+
+    fn action(name: &str, result: bool) -> (MockRecord, BT<Builder>) {
+        let mock = MockAction::new(name).results([result]);
+        (mock.record(), BT::action(mock))
+    }
+
+    // Ticks until the run finishes
+    async fn finish(ticker: &mut crate::Ticker) -> Status {
+        loop {
+            let status = ticker.tick().await;
+            if !status.is_running() {
+                return status;
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_once_returns_first_result() {
+        let (record, tree) = action("announce", false);
+        let mut ticker = BT::new().root(BT::run_once(tree)).into_ticker();
+
+        assert_eq!(finish(&mut ticker).await, Status::Failure);
+        assert_eq!(ticker.tick().await, Status::Failure); // Next run, without running the child
+        assert_eq!(ticker.tick().await, Status::Failure);
+        record.assert_calls(1);
+
+        ticker.reset().await;
+        assert_eq!(finish(&mut ticker).await, Status::Failure);
+        record.assert_calls(2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cooldown_blocks_until_period_passed() {
+        let (record, tree) = action("dock", false);
+        let mut ticker = BT::new().root(BT::cooldown(ms(300), tree)).into_ticker();
+
+        assert_eq!(finish(&mut ticker).await, Status::Failure);
+        record.assert_calls(1);
+
+        advance(ms(200)).await;
+        assert_eq!(ticker.tick().await, Status::Failure); // Still cooling down
+        record.assert_calls(1);
+
+        advance(ms(100)).await;
+        assert_eq!(ticker.tick().await, Status::Running);
+        assert_eq!(finish(&mut ticker).await, Status::Failure);
+        record.assert_calls(2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_allows_limit_per_period() {
+        let (record, tree) = action("retry", true);
+        let mut ticker = BT::new().root(BT::rate_limit(2, ms(1000), tree)).into_ticker();
+
+        assert_eq!(finish(&mut ticker).await, Status::Success);
+        advance(ms(100)).await;
+        assert_eq!(finish(&mut ticker).await, Status::Success);
+        advance(ms(100)).await;
+        assert_eq!(ticker.tick().await, Status::Failure); // Third start within the period
+        record.assert_calls(2);
+
+        advance(ms(850)).await; // The first start left the period
+        assert_eq!(finish(&mut ticker).await, Status::Success);
+        record.assert_calls(3);
+        assert_eq!(ticker.tick().await, Status::Failure);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reset_forgets_decorator_state() {
+        let (record, tree) = action("dock", true);
+        let mut ticker = BT::new().root(BT::cooldown(ms(1000), tree)).into_ticker();
+
+        assert_eq!(finish(&mut ticker).await, Status::Success);
+        assert_eq!(ticker.tick().await, Status::Failure);
+
+        ticker.reset().await;
+        assert_eq!(finish(&mut ticker).await, Status::Success);
+        record.assert_calls(2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_blocked_decorator_decides_tree_result() {
+        for engine in ENGINES {
+            let (first, first_tree) = action("first", true);
+            let (second, second_tree) = action("second", true);
+            let root = BT::seq(vec![first_tree, BT::rate_limit(0, ms(1000), second_tree)]);
+            let bt = BT::new().set_engine(engine).root(root);
+
+            assert_eq!(bt.run().await.result(), false);
+            first.assert_calls(1);
+            second.assert_not_called();
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_once_within_run() {
+        for engine in EVENT_ENGINES {
+            let handle = Handle::new(true);
+            let cond = BT::condition(handle.clone(), ClosureEvaluator::new("c".into(), |x: bool| x));
+            let (once, once_tree) = action("once", false);
+            let fallback = MockAction::new("fallback").delay(ms(300));
+            let fallback_rec = fallback.record();
+
+            // The condition re-enters the RunOnce, which fails again without running its child
+            let root = BT::fb(vec![BT::seq(vec![cond, BT::run_once(once_tree)]), BT::action(fallback)]);
+            let bt = BT::new().set_engine(engine).root(root);
+            let sim = Simulation::new()
+                .set(ms(50), &handle, false)
+                .set(ms(100), &handle, true)
+                .run(bt)
+                .await;
+
+            assert_eq!(sim.result, true);
+            once.assert_calls(1);
+            fallback_rec.assert_calls(1); // Selected again by both flips, so it kept running
+        }
+    }
+
+    // Fallback[Seq[cond, decorator(retry)], fallback], the condition drops at 200ms and returns at 250ms
+    async fn reenter(engine: Engines, decorator: impl FnOnce(BT<Builder>) -> BT<Builder>) -> (bool, MockRecord) {
+        let handle = Handle::new(true);
+        let cond = BT::condition(handle.clone(), ClosureEvaluator::new("c".into(), |x: bool| x));
+        let retry = MockAction::new("retry").delay(ms(50)).results([false, true]);
+        let record = retry.record();
+        let fallback = BT::action(MockAction::new("fallback").delay(ms(500)));
+
+        let root = BT::fb(vec![BT::seq(vec![cond, decorator(BT::action(retry))]), fallback]);
+        let bt = BT::new().set_engine(engine).root(root);
+        let sim = Simulation::new()
+            .set(ms(200), &handle, false)
+            .set(ms(250), &handle, true)
+            .run(bt)
+            .await;
+        (sim.result, record)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cooldown_rechecks_period_when_entered_again() {
+        for engine in EVENT_ENGINES {
+            // The period passed at 150ms, so the condition runs the child again at 250ms
+            let (result, record) = reenter(engine, |child| BT::cooldown(ms(100), child)).await;
+            assert_eq!(result, true);
+            record.assert_calls(2);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cooldown_blocks_when_entered_again_within_period() {
+        for engine in EVENT_ENGINES {
+            let (result, record) = reenter(engine, |child| BT::cooldown(ms(1000), child)).await;
+            assert_eq!(result, true); // The fallback finishes the tree
+            record.assert_calls(1);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_counts_each_entry() {
+        for engine in EVENT_ENGINES {
+            let (result, record) = reenter(engine, |child| BT::rate_limit(2, ms(1000), child)).await;
+            assert_eq!(result, true);
+            record.assert_calls(2);
+
+            let (_, record) = reenter(engine, |child| BT::rate_limit(1, ms(1000), child)).await;
+            record.assert_calls(1);
+        }
+    }
+
+    // Like reenter(), but the condition drops at 100ms and 300ms while the child is still running
+    async fn reenter_running(engine: Engines, limit: u32) -> (bool, MockRecord) {
+        let handle = Handle::new(true);
+        let cond = BT::condition(handle.clone(), ClosureEvaluator::new("c".into(), |x: bool| x));
+        let act = MockAction::new("act").delay(ms(1000));
+        let record = act.record();
+        let fallback = BT::action(MockAction::new("fallback").delay(ms(500)));
+
+        let root = BT::fb(vec![BT::seq(vec![cond, BT::rate_limit(limit, ms(10_000), BT::action(act))]), fallback]);
+        let bt = BT::new().set_engine(engine).root(root);
+        let sim = Simulation::new()
+            .set(ms(100), &handle, false)
+            .set(ms(200), &handle, true)
+            .set(ms(300), &handle, false)
+            .set(ms(400), &handle, true)
+            .run(bt)
+            .await;
+        (sim.result, record)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_counts_entry_after_preemption() {
+        for engine in ENGINES {
+            // The stopped child left the decorator, entering it again counts as a new start
+            let (result, record) = reenter_running(engine, 1).await;
+            assert_eq!(result, true);
+            record.assert_calls(1);
+
+            let (_, record) = reenter_running(engine, 2).await;
+            record.assert_calls(2);
+        }
+    }
+}
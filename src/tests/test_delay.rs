@@ -0,0 +1,99 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use actify::Handle;
+    use crate::{BT, Builder, Status, execution::engine_factory::Engines};
+    use crate::{testing::{MockAction, MockRecord}, tests::simulation::{Simulation, ms, ENGINES, EVENT_ENGINES}, nodes::condition::ClosureEvaluator};
+
+    fn condition(handle: &Handle<bool>) -> BT<Builder> {
+        BT::condition(handle.clone(), ClosureEvaluator::new("c".into(), |x: bool| x))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_delay_waits_before_child() {
+        for engine in EVENT_ENGINES {
+            let mock = MockAction::new("move").delay(ms(100));
+            let record = mock.record();
+            let bt = BT::new().set_engine(engine).root(BT::delay(ms(200), BT::action(mock)));
+            let sim = Simulation::new().run(bt).await;
+
+            assert_eq!(sim.result, true);
+            record.assert_calls(1);
+            assert_eq!(sim.timeline(), vec![
+                (0, "Delay", Status::Running),
+                (200, "Delay", Status::Success),
+                (200, "move", Status::Running),
+                (300, "move", Status::Success),
+            ]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_delay_cancelled_by_trigger() {
+        for engine in ENGINES {
+            let handle = Handle::new(true);
+            let attack = MockAction::new("attack");
+            let attack_rec = attack.record();
+            let retreat = MockAction::new("retreat").delay(ms(100));
+            let retreat_rec = retreat.record();
+
+            // The condition fails during the delay, so the attack never starts
+            let root = BT::fb(vec![
+                BT::seq(vec![condition(&handle), BT::delay(ms(300), BT::action(attack))]),
+                BT::action(retreat),
+            ]);
+            let bt = BT::new().set_engine(engine).root(root);
+            let sim = Simulation::new()
+                .set(ms(100), &handle, false)
+                .run(bt)
+                .await;
+
+            assert_eq!(sim.result, true);
+            attack_rec.assert_not_called();
+            retreat_rec.assert_calls(1);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_keep_running_until_failure() {
+        for engine in ENGINES {
+            let handle = Handle::new(true);
+            let patrol = MockAction::new("patrol").delay(ms(50)).results([true, true, false]);
+            let record = patrol.record();
+            let root = BT::keep_running_until_failure(BT::seq(vec![condition(&handle), BT::action(patrol)]));
+            let bt = BT::new().set_engine(engine).root(root);
+
+            assert_eq!(bt.run().await.result(), false);
+            record.assert_calls(3);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_keep_running_restarts_memory() {
+        for engine in ENGINES {
+            let first = MockAction::new("first");
+            let first_rec = first.record();
+            let second = MockAction::new("second").results([true, false]);
+            let root = BT::keep_running_until_failure(BT::seq_with_memory(vec![BT::action(first), BT::action(second)]));
+            let bt = BT::new().set_engine(engine).root(root);
+
+            assert_eq!(bt.run().await.result(), false);
+            first_rec.assert_calls(2);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_keep_running_stops_without_progress() {
+        for engine in ENGINES {
+            let mock = MockAction::new("announce");
+            let record = mock.record();
+
+            // After the first iteration the child succeeds without running anything, which would spin forever
+            let root = BT::keep_running_until_failure(BT::run_once(BT::action(mock)));
+            let bt = BT::new().set_engine(engine).root(root);
+
+            assert_eq!(bt.run().await.result(), false);
+            record.assert_calls(1);
+        }
+    }
+}
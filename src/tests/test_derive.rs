@@ -0,0 +1,118 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::{sync::{Arc, atomic::{AtomicBool, AtomicUsize, Ordering}}, time::Duration};
+    use actify::Handle;
+    use tokio::time::sleep;
+    use crate::{BT, Executor, Evaluator, tests::simulation::Simulation};
+
+    // Counts its own executions, which a #[bt_action] function can't since its arguments are cloned per call
+    #[derive(Executor)]
+    #[bt(name = "patrol", execute = "step", halt = "stop")]
+    struct Patrol {
+        steps: usize,
+        limit: usize,
+        done: Arc<AtomicUsize>,
+        halted: Arc<AtomicBool>,
+        duration: Duration,
+    }
+
+    impl Patrol {
+        fn new(limit: usize, duration: Duration) -> Patrol {
+            Patrol { steps: 0, limit, done: Arc::new(AtomicUsize::new(0)), halted: Arc::new(AtomicBool::new(false)), duration }
+        }
+
+        async fn step(&mut self) -> anyhow::Result<bool> {
+            sleep(self.duration).await;
+            self.steps += 1;
+            self.done.store(self.steps, Ordering::SeqCst);
+            Ok(self.steps < self.limit)
+        }
+
+        fn stop(&mut self) {
+            self.halted.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[derive(Clone, Evaluator)]
+    #[bt(evaluate = "check", value = "i32")]
+    struct Above {
+        limit: i32,
+    }
+
+    impl Above {
+        async fn check(&mut self, value: i32) -> bool {
+            value > self.limit
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_derive_executor_keeps_state() {
+        let patrol = Patrol::new(3, Duration::from_millis(10));
+        let done = patrol.done.clone();
+        assert_eq!(patrol.get_name(), "patrol");
+
+        let bt = BT::new().root(BT::keep_running_until_failure(BT::action(patrol)));
+        assert_eq!(bt.run().await.result(), false);
+        assert_eq!(done.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_derive_executor_halt() {
+        let handle = Handle::new(50);
+        let patrol = Patrol::new(10, Duration::from_millis(500));
+        let (done, halted) = (patrol.done.clone(), patrol.halted.clone());
+        assert_eq!(Above { limit: 0 }.get_name(), "Above");
+
+        let root = BT::fb(vec![
+            BT::seq(vec![BT::condition(handle.clone(), Above { limit: 20 }), BT::action(patrol)]),
+            BT::action_fn("dock", || async { Ok(true) }),
+        ]);
+        let sim = Simulation::new()
+            .set(Duration::from_millis(100), &handle, 10)
+            .run(BT::new().root(root))
+            .await;
+
+        assert_eq!(sim.result, true);
+        assert_eq!(done.load(Ordering::SeqCst), 0);
+        assert_eq!(halted.load(Ordering::SeqCst), true);
+    }
+
+    // Sync methods work the same, the derive can't tell them apart from the name
+    #[derive(Executor)]
+    #[bt(execute = "count")]
+    struct Counter {
+        count: usize,
+    }
+
+    impl Counter {
+        fn count(&mut self) -> anyhow::Result<bool> {
+            self.count += 1;
+            Ok(self.count < 3)
+        }
+    }
+
+    #[derive(Clone, Evaluator)]
+    #[bt(evaluate = "check", value = "i32")]
+    struct Positive {}
+
+    impl Positive {
+        fn check(&mut self, value: i32) -> bool {
+            value > 0
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_derive_sync_methods() {
+        let counter = Counter { count: 0 };
+        let root = BT::seq(vec![
+            BT::condition(Handle::new(1), Positive {}),
+            BT::keep_running_until_failure(BT::action(counter)),
+        ]);
+        assert_eq!(BT::new().root(root).run().await.result(), false);
+
+        let mut counter = Counter { count: 0 };
+        assert_eq!(counter.execute().await.unwrap(), true);
+        assert_eq!(Positive {}.evaluate(-1).await.unwrap(), false);
+    }
+}
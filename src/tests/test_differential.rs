@@ -0,0 +1,49 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use crate::tests::differential::{Generator, Spec, compare_engines, shrink};
+
+    const CASES: u64 = 300;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_static_and_dynamic_engines_agree() {
+        for seed in 0..CASES {
+            let spec = Generator::new(seed).tree(3);
+            if compare_engines(&spec).await.is_none() {
+                continue;
+            }
+
+            let minimal = shrink(spec, async |spec: &Spec| compare_engines(spec).await.is_some()).await;
+            let (static_run, dynamic_run) = compare_engines(&minimal).await.unwrap();
+            panic!(
+                "Engines disagree for seed {seed}, minimal tree: {minimal:?}\nstatic: {static_run:?}\ndynamic: {dynamic_run:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shrink_to_minimal_tree() {
+        let fails = async |spec: &Spec| contains_failing_action(spec);
+        let spec = Spec::Sequence(vec![
+            Spec::Condition { initial: true, flips: vec![15, 25] },
+            Spec::Fallback(vec![
+                Spec::Action(vec![(30, true)]),
+                Spec::Sequence(vec![Spec::Action(vec![(20, true), (40, false)])]),
+            ]),
+        ]);
+
+        let minimal = shrink(spec, fails).await;
+        assert_eq!(minimal, Spec::Action(vec![(10, false)]));
+    }
+
+    fn contains_failing_action(spec: &Spec) -> bool {
+        match spec {
+            Spec::Action(outcomes) => outcomes.iter().any(|(_, res)| !res),
+            Spec::Condition { .. } => false,
+            Spec::Constant(result) => !result,
+            Spec::Sequence(children) | Spec::Fallback(children) |
+            Spec::SequenceWithMemory(children) | Spec::FallbackWithMemory(children) |
+            Spec::RandomSequence(children) | Spec::RandomFallback(children) => children.iter().any(contains_failing_action),
+        }
+    }
+}
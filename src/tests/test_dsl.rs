@@ -0,0 +1,128 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::time::Duration;
+    use actify::Handle;
+    use crate::{BT, Builder, Status, Switch, UtilitySelector, bt, nodes_bin::{node_arena::NodeKind, node_info::NodeInfo}};
+    use crate::{testing::{MockAction, MockRecord}, nodes::condition::ClosureEvaluator, tests::simulation::ms};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Mode {
+        Dock,
+        Patrol,
+    }
+
+    // The nodes in arena order with their arguments, the processes by name instead of their random ids
+    fn nodes(tree: &BT<Builder>) -> Vec<String> {
+        tree.arena.indices()
+            .map(|idx| {
+                let kind = match tree.arena.kind(idx) {
+                    NodeKind::Action(_) => "Action".to_string(),
+                    NodeKind::Condition(_) => "Condition".to_string(),
+                    NodeKind::Switch(_) => "Switch".to_string(),
+                    NodeKind::Delay(_) => "Delay".to_string(),
+                    kind => format!("{kind:?}"),
+                };
+                let NodeInfo { name, description, metadata, .. } = NodeInfo::new(&tree.arena, &tree.map, idx);
+                let node = tree.arena.get(idx);
+                format!("{kind} {name} {description:?} {metadata:?} weight {} once {}", node.weight, node.checked_once)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_dsl_runs_like_builder() {
+        let handle = Handle::new(false);
+        let dock = MockAction::new("dock");
+        let dock_rec = dock.record();
+        let alarm = MockAction::new("alarm");
+        let alarm_rec = alarm.record();
+
+        let tree = bt! {
+            fb {
+                seq {
+                    cond(handle.clone(), ClosureEvaluator::new("battery_ok".into(), |x: bool| x)),
+                    action(MockAction::new("work")),
+                },
+                seq { action(dock), action(alarm) }
+            }
+        };
+
+        assert_eq!(BT::new().root(tree).run().await.result(), true);
+        dock_rec.assert_calls(1);
+        alarm_rec.assert_calls(1);
+    }
+
+    #[tokio::test]
+    async fn test_dsl_structure() {
+        let handle = Handle::new(1);
+        let subtree = || BT::action(MockAction::new("sub"));
+
+        let tree = bt! {
+            seq_with_memory {
+                run_once { action(MockAction::new("init")).name("start").meta("step", "1") },
+                cooldown(Duration::from_secs(1)) { cond(handle.clone(), ClosureEvaluator::new("c".into(), |x: i32| x > 0)) },
+                checked_once { cond(handle.clone(), ClosureEvaluator::new("d".into(), |x: i32| x < 5)) },
+                weighted { 1.0 => tree(subtree()), 2.0 => random_fb { tree(subtree()), tree(subtree()) } },
+                keep_running_until_failure { rate_limit(2, Duration::from_secs(1)) { delay(Duration::from_millis(10)) { tree(subtree()) } } },
+                utility("utility", handle.clone(), 0.5) { |x| *x as f64 => tree(subtree()), |x| 5.0 - *x as f64 => tree(subtree()) },
+                switch("mode", Handle::new(Mode::Dock)) { Mode::Dock => tree(subtree()), _ => success }.description("mode"),
+            }
+        };
+
+        let expected = BT::seq_with_memory(vec![
+            BT::run_once(BT::action(MockAction::new("init")).name("start").meta("step", "1")),
+            BT::cooldown(Duration::from_secs(1), BT::condition(handle.clone(), ClosureEvaluator::new("c".into(), |x: i32| x > 0))),
+            BT::checked_once(BT::condition(handle.clone(), ClosureEvaluator::new("d".into(), |x: i32| x < 5))),
+            BT::weighted(vec![(1.0, subtree()), (2.0, BT::random_fb(vec![subtree(), subtree()]))]),
+            BT::keep_running_until_failure(BT::rate_limit(2, Duration::from_secs(1), BT::delay(Duration::from_millis(10), subtree()))),
+            BT::utility(UtilitySelector::new("utility", handle.clone()).margin(0.5)
+                .child(|x| *x as f64, subtree())
+                .child(|x| 5.0 - *x as f64, subtree())),
+            BT::switch(Switch::new("mode", Handle::new(Mode::Dock)).case(Mode::Dock, subtree()).default(BT::success())).description("mode"),
+        ]);
+
+        assert_eq!(nodes(&tree), nodes(&expected));
+    }
+
+    #[tokio::test]
+    async fn test_dsl_named_tree() {
+        let bt = bt! { "robot": seq { action(MockAction::new("a")), action(MockAction::new("b")) } };
+        assert_eq!(bt.run().await.result(), true);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dsl_utility_runs_highest_score() {
+        let low = MockAction::new("low");
+        let low_rec = low.record();
+        let high = MockAction::new("high");
+        let high_rec = high.record();
+
+        let bt = bt! { "utility": utility("utility", Handle::new(2.0)) { |x| *x => action(low), |x| 2.0 * x => action(high) } };
+        assert_eq!(bt.run().await.result(), true);
+        low_rec.assert_not_called();
+        high_rec.assert_calls(1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dsl_switch_runs_matching_case() {
+        let dock = MockAction::new("dock");
+        let dock_rec = dock.record();
+        let patrol = MockAction::new("patrol").delay(ms(10));
+        let patrol_rec = patrol.record();
+        let other = MockAction::new("other");
+        let other_rec = other.record();
+
+        let bt = bt! {
+            "switch": switch("mode", Handle::new(Mode::Patrol)) {
+                Mode::Dock => action(dock),
+                Mode::Patrol => action(patrol),
+                _ => action(other),
+            }
+        };
+        assert_eq!(bt.run().await.result(), true);
+        dock_rec.assert_not_called();
+        patrol_rec.assert_calls(1);
+        other_rec.assert_not_called();
+    }
+}
@@ -1,416 +1,283 @@
 #[cfg(test)]
 #[allow(unused_imports)]
 mod tests {
-    use std::{collections::HashMap, time::Duration};
+    use std::collections::HashMap;
     use actify::Handle;
-    use tokio::time::sleep;
-    use crate::{BT, Condition, Failure, Success, Wait, bt::Ready, execution::engine_factory::Engines, logging::load_logger, nodes::action::mocking::MockAction, nodes_bin::{node::Node, node_status::Status}};
+    use crate::{Action, BT, Condition, Failure, Success, Wait, bt::Ready, execution::engine_factory::Engines, testing::MockAction, nodes_bin::{node::Node, node_map::NodeIdToProcessHandleMap, node_status::Status, process_handle::ProcessHandle}};
+    use crate::tests::simulation::{Simulation, ms, ENGINES};
 
+    fn tree(map: NodeIdToProcessHandleMap, root: Node, engine: Engines) -> BT<Ready> {
+        BT::new().test_insert_map(map).test_root(root).set_engine(engine).name("test_tree")
+    }
 
-    // Test for each engine type
-    const ENGINE: Engines = Engines::Dynamic;
+    fn action(name: &str, delay: u64) -> ProcessHandle {
+        Action::new(MockAction::new(name).delay(ms(delay)))
+    }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_simple_success() {
-        let mut map = HashMap::new();
-        let action = Success::new();
-        let id = "a1".to_string();
-        map.insert(id.clone(), action);
-
-        let root = Node::Action(id);
-        let bt = BT::new().test_insert_map(map).test_root(root).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), true);
+        for engine in ENGINES {
+            let map = HashMap::from([("a1".to_string(), Success::new())]);
+            let sim = Simulation::new().run(tree(map, Node::Action("a1".into()), engine)).await;
+            assert_eq!(sim.result, true);
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_simple_failure() {
-        let mut map = HashMap::new();
-        let action = Failure::new();
-        let id = "a1".to_string();
-        map.insert(id.clone(), action);
-
-        let root = Node::Action(id);
-        let bt = BT::new().test_insert_map(map).test_root(root).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), false);
+        for engine in ENGINES {
+            let map = HashMap::from([("a1".to_string(), Failure::new())]);
+            let sim = Simulation::new().run(tree(map, Node::Action("a1".into()), engine)).await;
+            assert_eq!(sim.result, false);
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_condition_true() {
-        let mut map = HashMap::new();
-        let id = "cond".to_string();
-        let cond = Condition::new("cond_true", Handle::new(10), |x| x > 0);
-        map.insert(id.clone(), cond);
-
-        let root = Node::Condition(id);
-        let bt = BT::new().test_insert_map(map).test_root(root).set_engine(ENGINE).name("test_tree");
-
-        let bt = bt.test_into_state().run().await;
-        assert_eq!(bt.result(), true);
+        for engine in ENGINES {
+            let map = HashMap::from([("cond".to_string(), Condition::new("cond_true", Handle::new(10), |x| x > 0))]);
+            let sim = Simulation::new().run(tree(map, Node::Condition("cond".into()), engine)).await;
+            assert_eq!(sim.result, true);
+            assert_eq!(sim.timeline(), vec![(0, "cond_true", Status::Success)]);
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_condition_false() {
-        let mut map = HashMap::new();
-        let id = "cond".to_string();
-        let cond = Condition::new("cond_false", Handle::new(0), |x| x > 5);
-        map.insert(id.clone(), cond);
-
-        let root = Node::Condition(id);
-        let bt = BT::new().test_insert_map(map).test_root(root).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), false);
+        for engine in ENGINES {
+            let map = HashMap::from([("cond".to_string(), Condition::new("cond_false", Handle::new(0), |x| x > 5))]);
+            let sim = Simulation::new().run(tree(map, Node::Condition("cond".into()), engine)).await;
+            assert_eq!(sim.result, false);
+            assert_eq!(sim.timeline(), vec![(0, "cond_false", Status::Failure)]);
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_sequence_all_success() {
-        let mut map = HashMap::new();
-
-        let a1 = Success::new();
-        let a2 = Success::new();
-        let id1 = "a1".to_string();
-        let id2 = "a2".to_string();
-        map.insert(id1.clone(), a1);
-        map.insert(id2.clone(), a2);
-
-        let seq = Node::Sequence(vec![Node::Action(id1), Node::Action(id2)]);
-        let bt = BT::new().test_insert_map(map).test_root(seq).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), true);
+        for engine in ENGINES {
+            let map = HashMap::from([("a1".to_string(), Success::new()), ("a2".to_string(), Success::new())]);
+            let seq = Node::Sequence(vec![Node::Action("a1".into()), Node::Action("a2".into())]);
+            let sim = Simulation::new().run(tree(map, seq, engine)).await;
+            assert_eq!(sim.result, true);
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_sequence_stops_on_failure() {
-        let mut map = HashMap::new();
-
-        let a1 = Success::new();
-        let a2 = Failure::new();
-        let id1 = "a1".to_string();
-        let id2 = "a2".to_string();
-        map.insert(id1.clone(), a1);
-        map.insert(id2.clone(), a2);
-
-        let seq = Node::Sequence(vec![Node::Action(id1), Node::Action(id2)]);
-        let bt = BT::new().test_insert_map(map).test_root(seq).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), false);
+        for engine in ENGINES {
+            let map = HashMap::from([("a1".to_string(), action("a1", 100)), ("a2".to_string(), Failure::new()), ("a3".to_string(), action("a3", 100))]);
+            let seq = Node::Sequence(vec![Node::Action("a1".into()), Node::Action("a2".into()), Node::Action("a3".into())]);
+            let sim = Simulation::new().run(tree(map, seq, engine)).await;
+
+            assert_eq!(sim.result, false);
+            assert!(sim.timeline().iter().all(|(_, name, _)| *name != "a3"));
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_fallback_first_success() {
-        let mut map = HashMap::new();
-
-        let s1 = Success::new();
-        let f1 = Failure::new();
-        let id1 = "s1".to_string();
-        let id2 = "f1".to_string();
-        map.insert(id1.clone(), s1);
-        map.insert(id2.clone(), f1);
-
-        let fb = Node::Fallback(vec![Node::Action(id1), Node::Action(id2)]);
-        let bt = BT::new().test_insert_map(map).test_root(fb).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), true);
+        for engine in ENGINES {
+            let map = HashMap::from([("s1".to_string(), action("s1", 100)), ("f1".to_string(), Failure::new())]);
+            let fb = Node::Fallback(vec![Node::Action("s1".into()), Node::Action("f1".into())]);
+            let sim = Simulation::new().run(tree(map, fb, engine)).await;
+
+            assert_eq!(sim.result, true);
+            assert!(sim.timeline().iter().all(|(_, name, _)| *name != "FAILURE"));
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_fallback_second_success() {
-        let mut map = HashMap::new();
-
-        let f1 = Failure::new();
-        let s1 = Success::new();
-        let id1 = "f1".to_string();
-        let id2 = "s1".to_string();
-        map.insert(id1.clone(), f1);
-        map.insert(id2.clone(), s1);
-
-        let fb = Node::Fallback(vec![Node::Action(id1), Node::Action(id2)]);
-        let bt = BT::new().test_insert_map(map).test_root(fb).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), true);
+        for engine in ENGINES {
+            let map = HashMap::from([("f1".to_string(), Failure::new()), ("s1".to_string(), Success::new())]);
+            let fb = Node::Fallback(vec![Node::Action("f1".into()), Node::Action("s1".into())]);
+            let sim = Simulation::new().run(tree(map, fb, engine)).await;
+            assert_eq!(sim.result, true);
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_fallback_all_fail() {
-        let mut map = HashMap::new();
-
-        let f1 = Failure::new();
-        let f2 = Failure::new();
-        let id1 = "f1".to_string();
-        let id2 = "f2".to_string();
-        map.insert(id1.clone(), f1);
-        map.insert(id2.clone(), f2);
-
-        let fb = Node::Fallback(vec![Node::Action(id1), Node::Action(id2)]);
-        let bt = BT::new().test_insert_map(map).test_root(fb).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), false);
+        for engine in ENGINES {
+            let map = HashMap::from([("f1".to_string(), Failure::new()), ("f2".to_string(), Failure::new())]);
+            let fb = Node::Fallback(vec![Node::Action("f1".into()), Node::Action("f2".into())]);
+            let sim = Simulation::new().run(tree(map, fb, engine)).await;
+            assert_eq!(sim.result, false);
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_nested_sequence_fallback() {
-        let mut map = HashMap::new();
-
-        let idc = "cond".to_string();
-        let idf1 = "f1".to_string();
-        let ids1 = "s1".to_string();
-
-        map.insert(idc.clone(), Condition::new("nested", Handle::new(0), |x| x > 0));
-        map.insert(idf1.clone(), Failure::new());
-        map.insert(ids1.clone(), Success::new());
-
-        let fb = Node::Fallback(vec![Node::Action(idf1), Node::Action(ids1)]);
-        let seq = Node::Sequence(vec![Node::Condition(idc), fb]);
-
-        let bt = BT::new().test_insert_map(map).test_root(seq).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), false);
+        for engine in ENGINES {
+            let map = HashMap::from([
+                ("cond".to_string(), Condition::new("nested", Handle::new(0), |x| x > 0)),
+                ("f1".to_string(), Failure::new()),
+                ("s1".to_string(), Success::new()),
+            ]);
+            let fb = Node::Fallback(vec![Node::Action("f1".into()), Node::Action("s1".into())]);
+            let seq = Node::Sequence(vec![Node::Condition("cond".into()), fb]);
+            let sim = Simulation::new().run(tree(map, seq, engine)).await;
+
+            assert_eq!(sim.result, false);
+            assert_eq!(sim.timeline(), vec![(0, "nested", Status::Failure)]);
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_wait_action() {
-        let mut map = HashMap::new();
-
-        let id = "wait".to_string();
-        map.insert(id.clone(), Wait::new(Duration::from_millis(50)));
-
-        let root = Node::Action(id);
-        let bt = BT::new().test_insert_map(map).test_root(root).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), true);
+        for engine in ENGINES {
+            let map = HashMap::from([("wait".to_string(), Wait::new(ms(50)))]);
+            let sim = Simulation::new().run(tree(map, Node::Action("wait".into()), engine)).await;
+
+            assert_eq!(sim.result, true);
+            // The tick engine sees the result on the tick after it
+            let finished = if matches!(engine, Engines::Tick(_)) { 60 } else { 50 };
+            assert_eq!(sim.timeline(), vec![(0, "Waiting", Status::Running), (finished, "Waiting", Status::Success)]);
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_condition_interrupt() {
-        let mut map = HashMap::new();
-
-        let handle = Handle::new(1);
-
-        let idc = "cond".to_string();
-        let ida = "action".to_string();
-        map.insert(idc.clone(), Condition::new("cond", handle.clone(), |x| x > 0));
-        map.insert(ida.clone(), MockAction::new(1));
-
-        let seq = Node::Sequence(vec![Node::Condition(idc), Node::Action(ida)]);
-        let bt = BT::new().test_insert_map(map).test_root(seq).set_engine(ENGINE).name("test_tree");
-
-        let (bt, _) = tokio::join!(
-            bt.test_into_state().run(),
-            async {
-                sleep(Duration::from_millis(200)).await;
-                handle.set(-1).await;
-            }
-        );
-
-        assert_eq!(bt.result(), false);
+        for engine in ENGINES {
+            let handle = Handle::new(1);
+            let map = HashMap::from([
+                ("cond".to_string(), Condition::new("cond", handle.clone(), |x| x > 0)),
+                ("action".to_string(), action("action", 500)),
+            ]);
+            let seq = Node::Sequence(vec![Node::Condition("cond".into()), Node::Action("action".into())]);
+            let sim = Simulation::new().set(ms(200), &handle, -1).run(tree(map, seq, engine)).await;
+
+            assert_eq!(sim.result, false);
+            assert!(sim.timeline().contains(&(200, "cond", Status::Failure)), "{engine:?}");
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_error_propagation_in_sequence() {
-        let mut map = HashMap::new();
-
-        let id1 = "a1".to_string();
-        let ide = "e".to_string();
-        let id2 = "a2".to_string();
-
-        map.insert(id1.clone(), MockAction::new(1));
-        map.insert(ide.clone(), MockAction::new_error(2));
-        map.insert(id2.clone(), MockAction::new(3));
-
-        let seq = Node::Sequence(vec![
-            Node::Action(id1),
-            Node::Action(ide),
-            Node::Action(id2),
-        ]);
-
-        let bt = BT::new().test_insert_map(map).test_root(seq).set_engine(ENGINE).name("test_tree");
-
-        let bt = bt.test_into_state().run().await;
-        assert_eq!(bt.result(), false);
+        for engine in ENGINES {
+            let map = HashMap::from([
+                ("a1".to_string(), action("1", 500)),
+                ("e".to_string(), Action::new(MockAction::new("2").delay(ms(500)).error("Some testing error!"))),
+                ("a2".to_string(), action("3", 500)),
+            ]);
+            let seq = Node::Sequence(vec![Node::Action("a1".into()), Node::Action("e".into()), Node::Action("a2".into())]);
+            let sim = Simulation::new().run(tree(map, seq, engine)).await;
+
+            assert_eq!(sim.result, false);
+            assert!(sim.timeline().iter().all(|(_, name, _)| *name != "3"));
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_two_conditions_switching() {
-        let mut map = HashMap::new();
-
-        let h1 = Handle::new(1);
-        let h2 = Handle::new(1);
-
-        let id1 = "c1".to_string();
-        let id2 = "c2".to_string();
-        let ida = "act".to_string();
-
-        map.insert(id1.clone(), Condition::new("cond1", h1.clone(), |x| x > 0));
-        map.insert(id2.clone(), Condition::new("cond2", h2.clone(), |x| x > 0));
-        map.insert(ida.clone(), MockAction::new(1));
-
-        let seq = Node::Sequence(vec![
-            Node::Condition(id1),
-            Node::Condition(id2),
-            Node::Action(ida),
-        ]);
-
-        let bt = BT::new().test_insert_map(map).test_root(seq).set_engine(ENGINE).name("test_tree");
-
-        let (bt, _, _) = tokio::join!(
-            bt.test_into_state().run(),
-            async {
-                sleep(Duration::from_millis(200)).await;
-                h2.set(0).await;
-            },
-            async {
-                sleep(Duration::from_millis(200)).await;
-                h1.set(0).await;
-            }
-        );
-
-        assert_eq!(bt.result(), false);
+        for engine in ENGINES {
+            let h1 = Handle::new(1);
+            let h2 = Handle::new(1);
+            let map = HashMap::from([
+                ("c1".to_string(), Condition::new("cond1", h1.clone(), |x| x > 0)),
+                ("c2".to_string(), Condition::new("cond2", h2.clone(), |x| x > 0)),
+                ("act".to_string(), action("act", 500)),
+            ]);
+            let seq = Node::Sequence(vec![Node::Condition("c1".into()), Node::Condition("c2".into()), Node::Action("act".into())]);
+            let sim = Simulation::new()
+                .set(ms(200), &h2, 0)
+                .set(ms(200), &h1, 0)
+                .run(tree(map, seq, engine))
+                .await;
+
+            assert_eq!(sim.result, false);
+            assert!(sim.timeline().iter().all(|(_, name, status)| (*name, *status) != ("act", Status::Success)));
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_condition_fails_mid_sequence() {
-        let mut map = HashMap::new();
-
-        let h1 = Handle::new(1);
-        let h2 = Handle::new(1);
-
-        let id1 = "c1".to_string();
-        let id2 = "c2".to_string();
-        let ida = "act".to_string();
-
-        map.insert(id1.clone(), Condition::new("cond1", h1.clone(), |x| x > 0));
-        map.insert(id2.clone(), Condition::new("cond2", h2.clone(), |x| x > 0));
-        map.insert(ida.clone(), MockAction::new(1));
-
-        let seq = Node::Sequence(vec![
-            Node::Condition(id1),
-            Node::Condition(id2),
-            Node::Action(ida),
-        ]);
-
-        let bt = BT::new().test_insert_map(map).test_root(seq).set_engine(ENGINE).name("test_tree");
-
-        let (bt, _) = tokio::join!(
-            bt.test_into_state().run(),
-            async {
-                sleep(Duration::from_millis(300)).await;
-                h2.set(0).await;
-            }
-        );
-
-        assert_eq!(bt.result(), false);
+        for engine in ENGINES {
+            let h1 = Handle::new(1);
+            let h2 = Handle::new(1);
+            let map = HashMap::from([
+                ("c1".to_string(), Condition::new("cond1", h1.clone(), |x| x > 0)),
+                ("c2".to_string(), Condition::new("cond2", h2.clone(), |x| x > 0)),
+                ("act".to_string(), action("act", 500)),
+            ]);
+            let seq = Node::Sequence(vec![Node::Condition("c1".into()), Node::Condition("c2".into()), Node::Action("act".into())]);
+            let sim = Simulation::new().set(ms(300), &h2, 0).run(tree(map, seq, engine)).await;
+
+            assert_eq!(sim.result, false);
+            assert!(sim.timeline().contains(&(300, "cond2", Status::Failure)), "{engine:?}");
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_multiple_conditions_toggle() {
-        let mut map = HashMap::new();
-
-        let h1 = Handle::new(0);
-        let h2 = Handle::new(0);
-        let h3 = Handle::new(0);
-
-        let id1 = "c1".to_string();
-        let id2 = "c2".to_string();
-        let id3 = "c3".to_string();
-        let ida = "a".to_string();
-
-        map.insert(id1.clone(), Condition::new("c1", h1.clone(), |x| x > 0));
-        map.insert(id2.clone(), Condition::new("c2", h2.clone(), |x| x > 0));
-        map.insert(id3.clone(), Condition::new("c3", h3.clone(), |x| x > 0));
-        map.insert(ida.clone(), MockAction::new(1));
-
-        let seq = Node::Fallback(vec![
-            Node::Condition(id1),
-            Node::Condition(id2),
-            Node::Condition(id3),
-            Node::Action(ida),
-        ]);
-
-        let bt = BT::new().test_insert_map(map).test_root(seq).set_engine(ENGINE).name("test_tree");
-
-        let (bt, _) = tokio::join!(
-            bt.test_into_state().run(),
-            async {
-                sleep(Duration::from_millis(100)).await;
-                h1.set(1).await;
-                sleep(Duration::from_millis(100)).await;
-                h2.set(1).await;
-                sleep(Duration::from_millis(100)).await;
-                h3.set(1).await;
-            }
-        );
-
-        assert_eq!(bt.result(), true);
+        for engine in ENGINES {
+            let (h1, h2, h3) = (Handle::new(0), Handle::new(0), Handle::new(0));
+            let map = HashMap::from([
+                ("c1".to_string(), Condition::new("c1", h1.clone(), |x| x > 0)),
+                ("c2".to_string(), Condition::new("c2", h2.clone(), |x| x > 0)),
+                ("c3".to_string(), Condition::new("c3", h3.clone(), |x| x > 0)),
+                ("a".to_string(), action("a", 500)),
+            ]);
+            let fb = Node::Fallback(vec![
+                Node::Condition("c1".into()),
+                Node::Condition("c2".into()),
+                Node::Condition("c3".into()),
+                Node::Action("a".into()),
+            ]);
+            let sim = Simulation::new()
+                .set(ms(100), &h1, 1)
+                .set(ms(200), &h2, 1)
+                .set(ms(300), &h3, 1)
+                .run(tree(map, fb, engine))
+                .await;
+
+            // The first condition that succeeds finishes the fallback
+            assert_eq!(sim.result, true);
+            assert!(sim.timeline().contains(&(100, "c1", Status::Success)), "{engine:?}");
+            assert!(sim.timeline().iter().all(|(_, name, status)| *name == "c1" || *status != Status::Success));
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_stop_monitoring_condition() {
-        let mut map = HashMap::new();
-
-        let h1 = Handle::new(1);
-        let h2 = Handle::new(0);
-
-        let id1 = "c1".to_string();
-        let id2 = "c2".to_string();
-        let ida = "a".to_string();
-
-        map.insert(id1.clone(), Condition::new("c1", h1.clone(), |x| x > 0));
-        map.insert(id2.clone(), Condition::new("c2", h2.clone(), |x| x > 0));
-        map.insert(ida.clone(), MockAction::new(1));
-
-        let seq = Node::Fallback(vec![
-            Node::Sequence(vec![
-                Node::Condition(id1),
-                Node::Condition(id2),
-            ]),
-            Node::Action(ida),
-        ]);
-
-        let bt = BT::new().test_insert_map(map).test_root(seq).set_engine(ENGINE).name("test_tree");
-
-        let (bt, _) = tokio::join!(
-            bt.test_into_state().run(),
-            async {
-                sleep(Duration::from_millis(100)).await;
-                h1.set(0).await;
-                sleep(Duration::from_millis(100)).await;
-                h2.set(1).await;
-            }
-        );
-
-        assert_eq!(bt.result(), true);
+        for engine in ENGINES {
+            let h1 = Handle::new(1);
+            let h2 = Handle::new(0);
+            let map = HashMap::from([
+                ("c1".to_string(), Condition::new("c1", h1.clone(), |x| x > 0)),
+                ("c2".to_string(), Condition::new("c2", h2.clone(), |x| x > 0)),
+                ("a".to_string(), action("a", 500)),
+            ]);
+            let fb = Node::Fallback(vec![
+                Node::Sequence(vec![Node::Condition("c1".into()), Node::Condition("c2".into())]),
+                Node::Action("a".into()),
+            ]);
+            let sim = Simulation::new()
+                .set(ms(100), &h1, 0)
+                .set(ms(200), &h2, 1) // Not monitored anymore
+                .run(tree(map, fb, engine))
+                .await;
+
+            assert_eq!(sim.result, true);
+            assert!(sim.timeline().contains(&(100, "c1", Status::Failure)), "{engine:?}");
+            assert!(sim.timeline().iter().all(|(_, name, status)| (*name, *status) != ("c2", Status::Success)));
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_empty_seq() {
-        let map = HashMap::new();
-
-        let root = Node::Sequence(vec![]);
-        let bt = BT::new().test_insert_map(map).test_root(root).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), false);
+        for engine in ENGINES {
+            let sim = Simulation::new().run(tree(HashMap::new(), Node::Sequence(vec![]), engine)).await;
+            assert_eq!(sim.result, false);
+        }
     }
 
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_execute_nested_empty_seq() {
-        let map = HashMap::new();
-
-        let root = Node::Sequence(vec![Node::Sequence(vec![])]);
-        let bt = BT::new().test_insert_map(map).test_root(root).set_engine(ENGINE).name("test_tree");
-
-        let result = bt.test_into_state().run().await;
-        assert_eq!(result.result(), false);
+        for engine in ENGINES {
+            let root = Node::Sequence(vec![Node::Sequence(vec![])]);
+            let sim = Simulation::new().run(tree(HashMap::new(), root, engine)).await;
+            assert_eq!(sim.result, false);
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::time::Duration;
+    use anyhow::Result;
+    use serde::Serialize;
+    use tokio::time::sleep;
+    use crate::{BT, Executor, Feedback, Progress, Status, TreeEvent, execution::engine_factory::Engines};
+
+    // Reports a percentage every 100 ms
+    struct Download {
+        feedback: Option<Feedback>,
+    }
+
+    #[derive(Serialize)]
+    struct Chunk {
+        bytes: usize,
+    }
+
+    impl Executor for Download {
+        fn get_name(&self) -> String {
+            "download".to_string()
+        }
+
+        fn set_feedback(&mut self, feedback: Feedback) {
+            self.feedback = Some(feedback);
+        }
+
+        async fn execute(&mut self) -> Result<bool> {
+            let feedback = self.feedback.as_ref().unwrap();
+            for percentage in [25.0, 50.0, 75.0] {
+                sleep(Duration::from_millis(100)).await;
+                feedback.percentage(percentage);
+            }
+            feedback.send(Progress::value(&Chunk { bytes: 1024 })?.with_message("done"));
+            Ok(true)
+        }
+    }
+
+    async fn run_download(engine: Engines) -> Vec<TreeEvent> {
+        let bt = BT::new().set_engine(engine).root(BT::action(Download { feedback: None }));
+        let mut events = bt.subscribe();
+        assert_eq!(bt.run().await.result(), true);
+
+        let mut received = vec![];
+        while let Ok(event) = events.try_recv() {
+            received.push(event);
+        }
+        received
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_progress_forwarded_to_subscribers() {
+        for engine in [Engines::Static, Engines::Dynamic, Engines::Tick(Duration::from_millis(10))] {
+            let events = run_download(engine).await;
+            assert!(events.iter().all(|e| e.node.name == "download"));
+
+            let progress: Vec<Progress> = events.iter().filter_map(|e| e.progress.clone()).collect();
+            assert_eq!(progress[..3], [Progress::percentage(25.0), Progress::percentage(50.0), Progress::percentage(75.0)]);
+            assert_eq!(progress[3].message.as_deref(), Some("done"));
+            assert_eq!(progress[3].value, Some(serde_json::json!({ "bytes": 1024 })));
+
+            // Progress is reported between the start and the end of the action
+            assert_eq!(events.first().map(|e| (e.status, e.progress.is_none())), Some((Status::Running, true)));
+            assert_eq!(events.last().map(|e| (e.status, e.progress.is_none())), Some((Status::Success, true)));
+        }
+    }
+}
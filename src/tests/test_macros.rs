@@ -0,0 +1,95 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::{fmt::Display, sync::{Arc, atomic::{AtomicUsize, Ordering}}};
+    use actify::Handle;
+
+    // Only the macros are imported, the generated code refers to the rest itself
+    use crate::{BT, bt_action, bt_condition};
+
+    #[bt_action]
+    fn check_sync(ok: bool) -> bool {
+        ok
+    }
+
+    #[bt_action]
+    async fn log_value<T: Display>(value: T) -> Result<bool, std::fmt::Error> {
+        let _ = value.to_string();
+        Ok(true)
+    }
+
+    #[bt_action]
+    fn parse_number<T>(text: String) -> anyhow::Result<bool>
+    where
+        T: std::str::FromStr,
+    {
+        Ok(text.parse::<T>().is_ok())
+    }
+
+    #[bt_condition]
+    fn above<T: PartialOrd>(value: T, limit: T) -> bool {
+        value > limit
+    }
+
+    #[derive(Clone)]
+    struct Robot {
+        docked: Arc<AtomicUsize>,
+    }
+
+    // One attribute on the block expands the actions and the conditions in there
+    #[bt_action]
+    impl Robot {
+        #[bt_action]
+        async fn dock(&self, times: usize) -> anyhow::Result<bool> {
+            self.docked.fetch_add(times, Ordering::SeqCst);
+            Ok(true)
+        }
+
+        #[bt_action]
+        fn fail() -> bool {
+            false
+        }
+
+        #[bt_condition]
+        fn battery_ok(&self, battery: i32) -> bool {
+            battery > 20 && self.docked.load(Ordering::SeqCst) < 10
+        }
+
+        fn docked(&self) -> usize {
+            self.docked.load(Ordering::SeqCst)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_macro_sync_and_generic() {
+        let bt = BT::new().root(BT::seq(vec![
+            BT::action(CheckSyncExecutor::new(true)),
+            BT::action(LogValueExecutor::<u32>::new(3)),
+            BT::action(ParseNumberExecutor::<u8>::new("42".into())),
+            BT::condition(Handle::new(5.0), AboveEvaluator::new(1.0)),
+        ]));
+        assert_eq!(bt.run().await.result(), true);
+
+        let bt = BT::new().root(BT::fb(vec![
+            BT::action(CheckSyncExecutor::new(false)),
+            BT::action(ParseNumberExecutor::<u8>::new("300".into())),
+            BT::condition(Handle::new(5), AboveEvaluator::new(10)),
+        ]));
+        assert_eq!(bt.run().await.result(), false);
+    }
+
+    #[tokio::test]
+    async fn test_macro_methods() {
+        let robot = Robot { docked: Arc::new(AtomicUsize::new(0)) };
+        let bt = BT::new().root(BT::seq(vec![
+            BT::condition(Handle::new(50), RobotBatteryOkEvaluator::new(robot.clone())),
+            BT::action(RobotDockExecutor::new(robot.clone(), 2)),
+            BT::action(RobotDockExecutor::new(robot.clone(), 3)),
+        ]));
+        assert_eq!(bt.run().await.result(), true);
+        assert_eq!(robot.docked(), 5);
+
+        let bt = BT::new().root(BT::fb(vec![BT::action(RobotFailExecutor::new())]));
+        assert_eq!(bt.run().await.result(), false);
+    }
+}
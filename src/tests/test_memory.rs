@@ -0,0 +1,154 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::collections::HashMap;
+    use actify::Handle;
+    use crate::{BT, Condition, Status, bt::Ready, execution::engine_factory::Engines, nodes_bin::{node::Node, node_map::NodeIdToProcessHandleMap}};
+    use crate::{Action, testing::MockAction, tests::simulation::{Simulation, ms, EVENT_ENGINES}};
+    use crate::execution::traversal::{TraversalState, search_next_with, search_start_with};
+    use crate::nodes_bin::node_arena::NodeIndex;
+
+    fn tree(map: NodeIdToProcessHandleMap, root: Node, engine: Engines) -> BT<Ready> {
+        BT::new().test_insert_map(map).test_root(root).set_engine(engine).name("test_tree")
+    }
+
+    fn leaf(bt: &BT<Ready>, id: &str) -> NodeIndex {
+        bt.arena.indices().find(|idx| bt.arena.get_id(*idx).is_some_and(|i| i == id)).unwrap()
+    }
+
+    // Battery check in front of a list of tasks, a recharge should not redo the tasks that are done
+    fn charging_tree(memory: bool) -> (NodeIdToProcessHandleMap, Node, Handle<bool>) {
+        let mut map = HashMap::new();
+        let charged = Handle::new(true);
+        map.insert("charged".to_string(), Condition::new("charged", charged.clone(), |x| x));
+        map.insert("charge".to_string(), Action::new(MockAction::new("charge").delay(ms(100))));
+        map.insert("a1".to_string(), Action::new(MockAction::new("a1").delay(ms(100))));
+        map.insert("a2".to_string(), Action::new(MockAction::new("a2").delay(ms(300))));
+
+        let children = vec![
+            Node::Fallback(vec![Node::Condition("charged".into()), Node::Action("charge".into())]),
+            Node::Action("a1".into()),
+            Node::Action("a2".into()),
+        ];
+        let root = match memory {
+            true => Node::SequenceWithMemory(children),
+            false => Node::Sequence(children),
+        };
+        (map, root, charged)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sequence_with_memory_resumes_after_trigger() {
+        for engine in EVENT_ENGINES {
+            let (map, root, charged) = charging_tree(true);
+            let sim = Simulation::new()
+                .set(ms(150), &charged, false)
+                .run(tree(map, root, engine))
+                .await;
+
+            assert_eq!(sim.result, true);
+            assert_eq!(sim.timeline(), vec![
+                (0, "charged", Status::Success),
+                (0, "a1", Status::Running),
+                (100, "a1", Status::Success),
+                (100, "a2", Status::Running),
+                (150, "charged", Status::Failure),
+                (150, "a2", Status::Idle),
+                (150, "charge", Status::Running),
+                (250, "charge", Status::Success),
+                (250, "a2", Status::Running), // a1 is remembered
+                (550, "a2", Status::Success),
+                (550, "charged", Status::Idle), // Monitored until the tree finished
+            ]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sequence_without_memory_restarts_after_trigger() {
+        for engine in EVENT_ENGINES {
+            let (map, root, charged) = charging_tree(false);
+            let sim = Simulation::new()
+                .set(ms(150), &charged, false)
+                .run(tree(map, root, engine))
+                .await;
+
+            assert_eq!(sim.result, true);
+            assert_eq!(&sim.timeline()[8..], vec![
+                (250, "a1", Status::Running),
+                (350, "a1", Status::Success),
+                (350, "a2", Status::Running),
+                (650, "a2", Status::Success),
+                (650, "charged", Status::Idle),
+            ]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fallback_with_memory_resumes_after_trigger() {
+        for engine in EVENT_ENGINES {
+            let mut map = HashMap::new();
+            let blocked = Handle::new(false);
+            map.insert("blocked".to_string(), Condition::new("blocked", blocked.clone(), |x| x));
+            map.insert("wait".to_string(), Action::new(MockAction::new("wait").delay(ms(100))));
+            map.insert("p1".to_string(), Action::new(MockAction::new("p1").delay(ms(100)).results([false])));
+            map.insert("p2".to_string(), Action::new(MockAction::new("p2").delay(ms(300))));
+
+            // Tries plans p1 and p2, pausing while blocked. The failed p1 is not tried again
+            let root = Node::FallbackWithMemory(vec![
+                Node::Sequence(vec![Node::Condition("blocked".into()), Node::Action("wait".into())]),
+                Node::Action("p1".into()),
+                Node::Action("p2".into()),
+            ]);
+            let sim = Simulation::new()
+                .set(ms(150), &blocked, true)
+                .run(tree(map, root, engine))
+                .await;
+
+            assert_eq!(sim.result, true);
+            assert_eq!(sim.timeline(), vec![
+                (0, "blocked", Status::Failure),
+                (0, "p1", Status::Running),
+                (100, "p1", Status::Failure),
+                (100, "p2", Status::Running),
+                (150, "blocked", Status::Success),
+                (150, "p2", Status::Idle),
+                (150, "wait", Status::Running),
+                (250, "wait", Status::Success),
+                (250, "blocked", Status::Idle),
+            ]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_resets_when_composite_finishes() {
+        let root = Node::Sequence(vec![
+            Node::SequenceWithMemory(vec![Node::Action("a1".into()), Node::Action("a2".into())]),
+            Node::Action("a3".into()),
+        ]);
+        let bt = BT::new().test_root(root);
+        let (a1, a2) = (leaf(&bt, "a1"), leaf(&bt, "a2"));
+        let mut state = TraversalState::default();
+
+        // Remembered while the memory sequence runs
+        assert_eq!(search_next_with(&bt.arena, &mut state, a1, &Status::Success), Some(a2));
+        assert_eq!(search_start_with(&bt.arena, &mut state), Some(a2));
+
+        // Forgotten once it failed
+        assert_eq!(search_next_with(&bt.arena, &mut state, a2, &Status::Failure), None);
+        assert_eq!(search_start_with(&bt.arena, &mut state), Some(a1));
+    }
+
+    #[tokio::test]
+    async fn test_builder_memory_nodes() {
+        let bt = BT::new().root(BT::seq_with_memory(vec![
+            BT::fb_with_memory(vec![BT::action(MockAction::new("a"))]),
+            BT::action(MockAction::new("b")),
+        ]));
+        let root = bt.arena.root();
+        let fb = bt.arena.children(root)[0];
+
+        assert_eq!(format!("{:?}", bt.arena.kind(root)), "SequenceWithMemory");
+        assert_eq!(format!("{:?}", bt.arena.kind(fb)), "FallbackWithMemory");
+        assert_eq!(BT::new().root(BT::seq_with_memory(vec![BT::action(MockAction::new("a"))])).run().await.result(), true);
+    }
+}
@@ -0,0 +1,184 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::collections::BTreeMap;
+    use actify::Handle;
+    use crate::{BT, Builder, NodeInfo, NodeStatistics, Status, TreeEvent, bt, bt_action, testing::MockAction};
+    use crate::{nodes::condition::ClosureEvaluator, tests::simulation::{Simulation, ms, EVENT_ENGINES}};
+
+    #[bt_action(name = "charge")]
+    fn charge_battery() -> bool {
+        true
+    }
+
+    // The infos of the nodes as they started, in order
+    async fn started(tree: BT<Builder>) -> Vec<NodeInfo> {
+        let bt = BT::new().root(tree);
+        let mut events = bt.subscribe();
+        bt.run().await;
+
+        let mut infos = vec![];
+        while let Ok(TreeEvent { node, status, .. }) = events.try_recv() {
+            if status == Status::Running {
+                infos.push(node);
+            }
+        }
+        infos
+    }
+
+    #[tokio::test]
+    async fn test_names_in_events() {
+        let tree = BT::seq(vec![
+            BT::action(MockAction::new("mock")).name("approach").description("Drive up to the dock"),
+            BT::fb(vec![BT::action(ChargeBatteryExecutor::new())]),
+        ])
+        .name("docking")
+        .meta("owner", "navigation")
+        .meta("priority", "high");
+
+        let infos = started(tree).await;
+        let names = infos.iter().map(|info| info.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["approach", "charge"]);
+        assert_eq!(infos[0].description.as_deref(), Some("Drive up to the dock"));
+        assert!(infos[0].metadata.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_names_on_composites() {
+        let mut bt = BT::new().root(BT::seq(vec![BT::action(MockAction::new("mock"))]).name("docking").meta("owner", "navigation"));
+        let mut debugger = bt.debugger();
+        let run = tokio::spawn(bt.run());
+
+        let state = debugger.wait_paused().await.unwrap();
+        let expected = NodeInfo {
+            id: None,
+            name: "docking".to_string(),
+            description: None,
+            metadata: BTreeMap::from([("owner".to_string(), "navigation".to_string())]),
+        };
+        assert_eq!(state.trace[0], expected);
+        assert_eq!(state.trace[1].name, "mock");
+        debugger.resume().await;
+        assert_eq!(run.await.unwrap().result(), true);
+    }
+
+    #[tokio::test]
+    async fn test_names_in_dsl() {
+        let tree = bt! {
+            seq {
+                action(MockAction::new("mock")).name("approach").meta("owner", "navigation"),
+                fb { action(ChargeBatteryExecutor::new()) }.name("recharge"),
+            }.name("docking").description("Docks the robot")
+        };
+        assert_eq!(tree.arena.get(tree.arena.root()).meta.name.as_deref(), Some("docking"));
+
+        let infos = started(tree).await;
+        assert_eq!(infos[0].name, "approach");
+        assert_eq!(infos[0].metadata.get("owner").map(String::as_str), Some("navigation"));
+        assert_eq!(infos[1].name, "charge");
+    }
+
+    #[tokio::test]
+    async fn test_names_reach_processes() {
+        // The process logs with the name of the node, not the one of its executor
+        let tree = BT::action(MockAction::new("mock")).name("approach");
+        let id = tree.arena.get_id(tree.arena.root()).unwrap();
+        assert_eq!(tree.map[id].name(), "approach");
+    }
+
+    #[tokio::test]
+    async fn test_names_in_export() {
+        let tree = BT::seq(vec![
+            BT::action(MockAction::new("mock")).name("approach").description("Drive up to the dock"),
+            BT::cooldown(ms(100), BT::action(ChargeBatteryExecutor::new())),
+        ])
+        .name("docking")
+        .meta("owner", "navigation");
+        let export = BT::new().name("robot").root(tree).export();
+
+        assert_eq!(export.name, "robot");
+        assert_eq!(export.root.node.name, "docking");
+        assert_eq!(export.root.kind, "Sequence");
+        assert_eq!(export.root.children[0].node.description.as_deref(), Some("Drive up to the dock"));
+        assert_eq!(export.root.children[1].kind, "Cooldown");
+        assert_eq!(export.root.children[1].children[0].node.name, "charge");
+
+        let json = serde_json::to_value(&export).unwrap();
+        assert_eq!(json["root"]["name"], "docking");
+        assert_eq!(json["root"]["metadata"]["owner"], "navigation");
+        assert_eq!(json["root"]["children"][0]["name"], "approach");
+        assert_eq!(json["root"]["children"][1]["children"][0]["kind"], "Action");
+    }
+
+    fn counts(stats: &NodeStatistics) -> (&str, u32, u32, u32, u32) {
+        (stats.node.name.as_str(), stats.starts, stats.successes, stats.failures, stats.halts)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_names_in_statistics() {
+        let tree = BT::seq(vec![
+            BT::action(MockAction::new("mock").delay(ms(100))).name("approach").meta("owner", "navigation"),
+            BT::fb(vec![BT::action(MockAction::new("dock").results([false])), BT::action(ChargeBatteryExecutor::new())]),
+        ]);
+        let bt = BT::new().root(tree);
+        let mut statistics = bt.statistics();
+        assert_eq!(bt.run().await.result(), true);
+
+        let stats = statistics.get();
+        let counts = stats.iter().map(counts).collect::<Vec<_>>();
+        assert_eq!(counts, vec![("approach", 1, 1, 0, 0), ("dock", 1, 0, 1, 0), ("charge", 1, 1, 0, 0)]);
+        assert_eq!(stats[0].node.metadata.get("owner").map(String::as_str), Some("navigation"));
+        assert_eq!(stats[0].running_time, ms(100));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_statistics_count_halts() {
+        for engine in EVENT_ENGINES {
+            // The condition drops at 50ms and returns at 100ms, halting the patrol in between
+            let handle = Handle::new(true);
+            let cond = BT::condition(handle.clone(), ClosureEvaluator::new("c".into(), |x: bool| x));
+            let patrol = BT::action(MockAction::new("mock").delay(ms(200))).name("patrol");
+            let fallback = BT::action(MockAction::new("fallback").delay(ms(500)));
+            let bt = BT::new().set_engine(engine).root(BT::fb(vec![BT::seq(vec![cond, patrol]), fallback]));
+            let mut statistics = bt.statistics();
+            Simulation::new().set(ms(50), &handle, false).set(ms(100), &handle, true).run(bt).await;
+
+            let stats = statistics.get();
+            let patrol = stats.iter().find(|stats| stats.node.name == "patrol").unwrap();
+            assert_eq!(counts(patrol), ("patrol", 2, 1, 0, 1));
+            assert_eq!(patrol.running_time, ms(250));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_statistics_close_running_nodes() {
+        for engine in EVENT_ENGINES {
+            // The first condition drops at 100ms, which selects the running patrol again
+            let (h1, h2) = (Handle::new(true), Handle::new(false));
+            let c1 = BT::condition(h1.clone(), ClosureEvaluator::new("c1".into(), |x: bool| x));
+            let c2 = BT::condition(h2.clone(), ClosureEvaluator::new("c2".into(), |x: bool| x));
+            let patrol = BT::action(MockAction::new("mock").delay(ms(500))).name("patrol");
+            let bt = BT::new().set_engine(engine).root(BT::fb(vec![BT::seq(vec![c1, c2]), patrol]));
+            let mut statistics = bt.statistics();
+            Simulation::new().set(ms(100), &h1, false).run(bt).await;
+
+            let stats = statistics.get();
+            let patrol = stats.iter().find(|stats| stats.node.name == "patrol").unwrap();
+            assert_eq!(counts(patrol), ("patrol", 1, 1, 0, 0));
+            assert_eq!(patrol.running_time, ms(500));
+
+            // The condition fails the tree at 200ms, while the patrol still runs
+            let handle = Handle::new(true);
+            let cond = BT::condition(handle.clone(), ClosureEvaluator::new("c".into(), |x: bool| x));
+            let patrol = BT::action(MockAction::new("mock").delay(ms(500))).name("patrol");
+            let bt = BT::new().set_engine(engine).root(BT::seq(vec![cond, patrol]));
+            let mut statistics = bt.statistics();
+            Simulation::new().set(ms(200), &handle, false).run(bt).await;
+
+            let stats = statistics.get();
+            let patrol = stats.iter().find(|stats| stats.node.name == "patrol").unwrap();
+            assert_eq!(counts(patrol), ("patrol", 1, 0, 0, 1));
+            assert_eq!(patrol.running_time, ms(200));
+        }
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use crate::{BT, Builder, bt::Ready, execution::{engine_factory::Engines, static_engine::converter::convert_bt}, nodes_bin::node_arena::{NodeIndex, NodeKind}};
+    use crate::{testing::MockAction, tests::differential::{Generator, Run, run_optimized, run_spec}};
+
+    const CASES: u64 = 200;
+
+    fn action(name: &str) -> BT<Builder> {
+        BT::action(MockAction::new(name))
+    }
+
+    // The tree as text, e.g. Sequence(a, b)
+    fn shape(bt: &BT<Ready>, idx: NodeIndex) -> String {
+        let children = bt.arena.children(idx).iter().map(|child| shape(bt, *child)).collect::<Vec<_>>();
+        let name = match (bt.arena.kind(idx), &bt.arena.get(idx).meta.name) {
+            (_, Some(name)) => name.clone(),
+            (NodeKind::Action(id), None) => bt.map[id].name().to_string(),
+            (kind, None) => format!("{:?}", kind),
+        };
+        match children.is_empty() {
+            true => name,
+            false => format!("{}({})", name, children.join(", ")),
+        }
+    }
+
+    fn optimized(tree: BT<Builder>) -> String {
+        let bt = BT::new().root(tree).optimize();
+        shape(&bt, bt.arena.root())
+    }
+
+    #[tokio::test]
+    async fn test_optimize_flattens_nested() {
+        let tree = BT::seq(vec![BT::seq(vec![action("a"), action("b")]), BT::success(), BT::seq(vec![action("c")])]);
+        assert_eq!(optimized(tree), "Sequence(a, b, c)");
+
+        let tree = BT::fb(vec![BT::fb(vec![action("a"), BT::seq(vec![action("b"), action("c")])]), action("d")]);
+        assert_eq!(optimized(tree), "Fallback(a, Sequence(b, c), d)");
+    }
+
+    #[tokio::test]
+    async fn test_optimize_folds_constants() {
+        assert_eq!(optimized(BT::fb(vec![BT::failure(), BT::seq(vec![BT::success(), action("a")])])), "a");
+        assert_eq!(optimized(BT::seq(vec![action("a"), BT::failure(), action("b")])), "Sequence(a, FAILURE)");
+        assert_eq!(optimized(BT::seq(vec![BT::success(), BT::success()])), "SUCCESS");
+        assert_eq!(optimized(BT::fb(vec![action("a"), BT::seq(vec![BT::success()]), action("b")])), "Fallback(a, SUCCESS)");
+    }
+
+    #[tokio::test]
+    async fn test_optimize_keeps_named_and_stateful() {
+        assert_eq!(optimized(BT::seq(vec![BT::seq(vec![action("a")]).name("approach")])), "approach(a)");
+        assert_eq!(optimized(BT::seq_with_memory(vec![action("a")])), "SequenceWithMemory(a)");
+        assert_eq!(optimized(BT::random_seq(vec![BT::success(), action("a")])), "RandomSequence(SUCCESS, a)");
+        assert_eq!(optimized(BT::run_once(BT::seq(vec![action("a")]))), "RunOnce(a)");
+    }
+
+    #[tokio::test]
+    async fn test_optimize_keeps_named_constants() {
+        assert_eq!(optimized(BT::seq(vec![BT::success().name("docked"), action("a")])), "Sequence(docked, a)");
+        assert_eq!(optimized(BT::fb(vec![BT::failure().meta("owner", "navigation"), action("a")])), "Fallback(FAILURE, a)");
+        assert_eq!(optimized(BT::seq(vec![action("a"), BT::failure().name("abort"), action("b")])), "Sequence(a, abort, b)");
+    }
+
+    #[tokio::test]
+    async fn test_optimize_shrinks_map() {
+        let tree = || BT::seq(vec![BT::seq(vec![action("a"), BT::success()]), BT::fb(vec![BT::failure(), action("b")])]);
+        let original = BT::new().root(tree());
+        let optimized = BT::new().root(tree()).optimize();
+
+        assert_eq!(optimized.map.len(), 2);
+        assert!(convert_bt(&optimized).len() < convert_bt(&original).len());
+    }
+
+    // The constant leaves are gone after optimizing, the rest runs the same. A condition before a
+    // constant that finished the tree is still monitored and reported Idle, so those are left out
+    fn without_constants(run: Run) -> (bool, Vec<(u128, String, crate::Status)>) {
+        let end = run.transitions.last().map(|t| t.at);
+        let transitions = run.transitions.into_iter()
+            .filter(|t| t.name != "SUCCESS" && t.name != "FAILURE")
+            .filter(|t| !(t.status == crate::Status::Idle && Some(t.at) == end))
+            .map(|t| (t.at.as_millis(), t.name, t.status))
+            .collect();
+        (run.result, transitions)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_optimized_trees_are_equivalent() {
+        for seed in 0..CASES {
+            let spec = Generator::new(seed).tree(3);
+            for engine in [Engines::Static, Engines::Dynamic] {
+                let original = without_constants(run_spec(&spec, engine).await);
+                let optimized = without_constants(run_optimized(&spec, engine).await);
+                assert_eq!(original, optimized, "Optimizing changed the run for seed {seed}: {spec:?}");
+            }
+        }
+    }
+}
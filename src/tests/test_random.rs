@@ -0,0 +1,103 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::collections::HashMap;
+    use crate::{BT, Builder, Status, bt::Ready, execution::engine_factory::Engines, nodes_bin::{node::Node, node_map::NodeIdToProcessHandleMap}};
+    use crate::{Action, testing::{MockAction, MockRecord}, tests::simulation::{Simulation, ms, EVENT_ENGINES}};
+    use crate::execution::traversal::{TraversalState, search_start_with};
+
+    const NAMES: [&str; 4] = ["a", "b", "c", "d"];
+
+    // Failing actions of 10 ms, so a fallback tries them all
+    fn failing_actions() -> (NodeIdToProcessHandleMap, Vec<Node>) {
+        let mut map = HashMap::new();
+        for name in NAMES {
+            map.insert(name.to_string(), Action::new(MockAction::new(name).delay(ms(10)).results([false])));
+        }
+        (map, NAMES.iter().map(|name| Node::Action(name.to_string())).collect())
+    }
+
+    // Names of the actions in the order they were started
+    async fn started(bt: BT<Ready>) -> Vec<String> {
+        let sim = Simulation::new().run(bt).await;
+        sim.transitions.into_iter()
+            .filter(|t| t.status == Status::Running)
+            .map(|t| t.name)
+            .collect()
+    }
+
+    async fn random_fallback_order(engine: Engines, seed: u64) -> Vec<String> {
+        let (map, children) = failing_actions();
+        let bt = BT::new().test_insert_map(map).test_root(Node::RandomFallback(children)).set_engine(engine).seed(seed);
+        started(bt).await
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_random_fallback_tries_all_children_once() {
+        for engine in EVENT_ENGINES {
+            let mut order = random_fallback_order(engine, 3).await;
+            order.sort();
+            assert_eq!(order, NAMES);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_same_seed_same_order_in_both_engines() {
+        let mut orders = vec![];
+        for seed in 0..5 {
+            let order = random_fallback_order(Engines::Static, seed).await;
+            assert_eq!(order, random_fallback_order(Engines::Dynamic, seed).await);
+            assert_eq!(order, random_fallback_order(Engines::Dynamic, seed).await);
+            orders.push(order);
+        }
+        orders.dedup();
+        assert!(orders.len() > 1, "All seeds gave the same order: {orders:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_random_sequence_runs_each_child() {
+        let (records, children): (Vec<MockRecord>, Vec<BT<Builder>>) = NAMES.iter().map(|name| {
+            let mock = MockAction::new(*name);
+            (mock.record(), BT::action(mock))
+        }).unzip();
+
+        let bt = BT::new().seed(1).root(BT::random_seq(children));
+        assert_eq!(bt.run().await.result(), true);
+        records.iter().for_each(|record| record.assert_calls(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_weighted_choice_runs_one_child() {
+        for engine in EVENT_ENGINES {
+            for seed in 0..5 {
+                let a = MockAction::new("a").results([false]);
+                let b = MockAction::new("b");
+                let (rec_a, rec_b) = (a.record(), b.record());
+
+                let bt = BT::new().set_engine(engine).seed(seed).root(BT::weighted(vec![
+                    (0.0, BT::action(a)), // Never picked
+                    (1.0, BT::action(b)),
+                ]));
+                assert_eq!(bt.run().await.result(), true);
+                rec_a.assert_not_called();
+                rec_b.assert_calls(1);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_weighted_choice_follows_weights() {
+        let root = Node::WeightedChoice(vec![(3, Node::Action("a".into())), (1, Node::Action("b".into()))]);
+        let bt = BT::new().test_root(root);
+        let a = bt.arena.children(bt.arena.root())[0];
+
+        let mut state = TraversalState::new(Some(42));
+        let picks_a = (0..1000)
+            .filter(|_| {
+                state.clear(); // A new run draws again
+                search_start_with(&bt.arena, &mut state) == Some(a)
+            })
+            .count();
+        assert!((700..800).contains(&picks_a), "Picked a {picks_a} out of 1000 times");
+    }
+}
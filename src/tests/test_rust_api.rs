@@ -1,7 +1,7 @@
 #[cfg(test)]
 #[allow(unused_imports)]
 mod tests {
-    use std::{collections::HashMap, prelude::rust_2024::Future, time::Duration};
+    use std::{collections::HashMap, prelude::rust_2024::Future, sync::{Arc, atomic::{AtomicUsize, Ordering}}, time::Duration};
 
     use actify::Handle;
     use anyhow::{Error, Ok, Result};
@@ -9,7 +9,7 @@ mod tests {
     use tokio::time::sleep;
     use macros::{bt_action, bt_condition};
 
-    use crate::{Action, BT, Condition, Failure, Success, Wait, bt::Ready, nodes::{action::{Executor, mocking::MockAction}, condition::Evaluator}, nodes_bin::{node::Node, node_status::Status}};
+    use crate::{Action, BT, Condition, Failure, Success, Wait, bt::Ready, testing::MockAction, nodes::{action::Executor, condition::{ClosureEvaluator, Evaluator}}, nodes_bin::{node::Node, node_status::Status}};
 
     struct TestExecutor {}
 
@@ -193,4 +193,110 @@ mod tests {
 
         assert_eq!(bt.result(), false);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_action_fn() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let handle = Handle::new(0);
+        let setter = handle.clone();
+
+        let bt = BT::new()
+            .name("test_tree")
+            .root(
+                BT::seq(vec![
+                    BT::action_fn("count", move || {
+                        let calls = counter.clone();
+                        let handle = setter.clone();
+                        async move {
+                            let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                            handle.set(n as i32).await;
+                            Ok(true)
+                        }
+                    }),
+                    BT::condition(handle.clone(), ClosureEvaluator::new("is_set".into(), |x: i32| x == 1)),
+                ])
+            )
+            .run().await;
+
+        assert_eq!(bt.result(), true);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(handle.get().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_action_from_fn_failure() {
+        let mut map = HashMap::new();
+        map.insert("a1".to_string(), Action::from_fn("fails", || async { Ok(false) }));
+        map.insert("a2".to_string(), Action::from_fn("errors", || async { Err(anyhow::anyhow!("Some testing error!")) }));
+
+        let root = Node::Fallback(vec![Node::Action("a1".into()), Node::Action("a2".into())]);
+        let bt = BT::new().test_insert_map(map).test_root(root).name("test_tree");
+
+        assert_eq!(bt.run().await.result(), false);
+    }
+
+    #[tokio::test]
+    async fn test_condition_multiple_handles() {
+        let battery = Handle::new(50);
+        let estop = Handle::new(false);
+        let bt = BT::new()
+            .name("test_tree")
+            .root(
+                BT::seq(vec![
+                    BT::condition((battery.clone(), estop.clone()), ClosureEvaluator::new("safe".into(), |(battery, estop): (i32, bool)| battery > 20 && !estop)),
+                    BT::action(BarExecutor::new(400)),
+                ])
+            );
+
+        let (bt, _) = tokio::join!(
+            bt.run(),
+            async {
+                sleep(Duration::from_millis(100)).await;
+                battery.set(30).await; // Still safe
+                sleep(Duration::from_millis(100)).await;
+                estop.set(true).await;
+            }
+        );
+
+        assert_eq!(bt.result(), false);
+    }
+
+    #[bt_condition(watch = [battery, estop])]
+    async fn safe_to_drive(battery: i32, min_battery: i32, estop: bool) -> Result<bool, Error> {
+        Ok(battery > min_battery && !estop)
+    }
+
+    #[tokio::test]
+    async fn test_macro_cond_watch() {
+        let battery = Handle::new(50);
+        let estop = Handle::new(false);
+        let bt = BT::new()
+            .name("test_tree")
+            .root(
+                BT::seq(vec![
+                    BT::condition((battery.clone(), estop.clone()), SafeToDriveEvaluator::new(20)),
+                    BT::action(BarExecutor::new(400)),
+                ])
+            );
+
+        let (bt, _) = tokio::join!(
+            bt.run(),
+            async {
+                sleep(Duration::from_millis(100)).await;
+                battery.set(10).await;
+            }
+        );
+        assert_eq!(bt.result(), false);
+
+        let bt = BT::new()
+            .name("test_tree")
+            .root(
+                BT::seq(vec![
+                    BT::condition((battery.clone(), estop.clone()), SafeToDriveEvaluator::new(5)),
+                    BT::action(BarExecutor::new(100)),
+                ])
+            );
+        assert_eq!(bt.run().await.result(), true);
+    }
+}
@@ -0,0 +1,93 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::collections::HashMap;
+    use actify::Handle;
+    use crate::{BT, Condition, Status, bt::Ready, execution::engine_factory::Engines, nodes_bin::{node::Node, node_map::NodeIdToProcessHandleMap}};
+    use crate::{Action, testing::MockAction, tests::simulation::{Simulation, ms, EVENT_ENGINES}};
+
+    fn tree(map: NodeIdToProcessHandleMap, root: Node, engine: Engines) -> BT<Ready> {
+        BT::new().test_insert_map(map).test_root(root).set_engine(engine).name("test_tree")
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sim_fallback_retries_next_child() {
+        for engine in EVENT_ENGINES {
+            let mut map = HashMap::new();
+            map.insert("a".to_string(), Action::new(MockAction::new("a").delay(ms(100)).results([false])));
+            map.insert("b".to_string(), Action::new(MockAction::new("b").delay(ms(300)).results([true])));
+
+            let fb = Node::Fallback(vec![Node::Action("a".into()), Node::Action("b".into())]);
+            let sim = Simulation::new().run(tree(map, fb, engine)).await;
+
+            assert_eq!(sim.result, true);
+            assert_eq!(sim.timeline(), vec![
+                (0, "a", Status::Running),
+                (100, "a", Status::Failure),
+                (100, "b", Status::Running),
+                (400, "b", Status::Success),
+            ]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sim_condition_interrupt() {
+        for engine in EVENT_ENGINES {
+            let mut map = HashMap::new();
+            let handle = Handle::new(1);
+            map.insert("cond".to_string(), Condition::new("cond", handle.clone(), |x| x > 0));
+            map.insert("act".to_string(), Action::new(MockAction::new("act").delay(ms(500)).results([true])));
+
+            let seq = Node::Sequence(vec![Node::Condition("cond".into()), Node::Action("act".into())]);
+            let sim = Simulation::new()
+                .set(ms(200), &handle, -1)
+                .run(tree(map, seq, engine))
+                .await;
+
+            assert_eq!(sim.result, false);
+            assert_eq!(sim.timeline(), vec![
+                (0, "cond", Status::Success),
+                (0, "act", Status::Running),
+                (200, "cond", Status::Failure),
+                (200, "act", Status::Idle), // Still running when the tree finished
+            ]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sim_stop_monitoring_condition() {
+        for engine in EVENT_ENGINES {
+            let mut map = HashMap::new();
+            let h1 = Handle::new(1);
+            let h2 = Handle::new(0);
+            map.insert("c1".to_string(), Condition::new("c1", h1.clone(), |x| x > 0));
+            map.insert("c2".to_string(), Condition::new("c2", h2.clone(), |x| x > 0));
+            let mock = MockAction::new("act").delay(ms(500)).results([true]);
+            let record = mock.record();
+            map.insert("act".to_string(), Action::new(mock));
+
+            let root = Node::Fallback(vec![
+                Node::Sequence(vec![Node::Condition("c1".into()), Node::Condition("c2".into())]),
+                Node::Action("act".into()),
+            ]);
+            let sim = Simulation::new()
+                .set(ms(100), &h1, 0)
+                .set(ms(200), &h2, 1) // Not monitored anymore
+                .run(tree(map, root, engine))
+                .await;
+
+            assert_eq!(sim.result, true);
+            assert_eq!(sim.timeline(), vec![
+                (0, "c1", Status::Success),
+                (0, "c2", Status::Failure),
+                (0, "act", Status::Running),
+                (100, "c1", Status::Failure),
+                (100, "c2", Status::Idle),
+                (500, "act", Status::Success), // Reselected, so it kept running
+                (500, "c1", Status::Idle),
+            ]);
+            record.assert_calls(1);
+            assert_eq!(record.halts(), 0);
+        }
+    }
+}
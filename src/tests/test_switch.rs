@@ -0,0 +1,134 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use actify::Handle;
+    use crate::{BT, Builder, Status, Switch, bt::Ready, execution::engine_factory::Engines};
+    use crate::{testing::{MockAction, MockRecord}, tests::simulation::{Simulation, ms, ENGINES, EVENT_ENGINES}};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Mode {
+        Dock,
+        Patrol,
+        Manual,
+    }
+
+    fn action(name: &str, delay: u64) -> (MockRecord, BT<Builder>) {
+        let mock = MockAction::new(name).delay(ms(delay));
+        (mock.record(), BT::action(mock))
+    }
+
+    fn switch(mode: &Handle<Mode>) -> (Switch<Handle<Mode>>, MockRecord, MockRecord, MockRecord) {
+        let (dock, dock_tree) = action("dock", 300);
+        let (patrol, patrol_tree) = action("patrol", 300);
+        let (other, other_tree) = action("other", 300);
+        let switch = Switch::new("mode", mode.clone())
+            .case(Mode::Dock, dock_tree)
+            .case(Mode::Patrol, patrol_tree)
+            .default(other_tree);
+        (switch, dock, patrol, other)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_switch_runs_matching_case() {
+        for engine in ENGINES {
+            let mode = Handle::new(Mode::Patrol);
+            let (switch, dock, patrol, other) = switch(&mode);
+            let bt = BT::new().set_engine(engine).root(BT::switch(switch));
+
+            assert_eq!(bt.run().await.result(), true);
+            dock.assert_not_called();
+            patrol.assert_calls(1);
+            other.assert_not_called();
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_switch_runs_default() {
+        for engine in ENGINES {
+            let mode = Handle::new(Mode::Manual);
+            let (switch, dock, patrol, other) = switch(&mode);
+            let bt = BT::new().set_engine(engine).root(BT::switch(switch));
+
+            assert_eq!(bt.run().await.result(), true);
+            dock.assert_not_called();
+            patrol.assert_not_called();
+            other.assert_calls(1);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_switch_without_default_fails() {
+        for engine in ENGINES {
+            let (dock, dock_tree) = action("dock", 10);
+            let switch = Switch::new("mode", Handle::new(Mode::Manual)).case(Mode::Dock, dock_tree);
+            let bt = BT::new().set_engine(engine).root(BT::switch(switch));
+
+            assert_eq!(bt.run().await.result(), false);
+            dock.assert_not_called();
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_switch_changes_branch() {
+        for engine in EVENT_ENGINES {
+            let mode = Handle::new(Mode::Patrol);
+            let (switch, dock, patrol, _) = switch(&mode);
+            let bt = BT::new().set_engine(engine).root(BT::switch(switch));
+            let sim = Simulation::new().set(ms(100), &mode, Mode::Dock).run(bt).await;
+
+            assert_eq!(sim.result, true);
+            assert_eq!(sim.timeline(), vec![
+                (0, "mode", Status::Success),
+                (0, "patrol", Status::Running),
+                (100, "mode", Status::Success),
+                (100, "patrol", Status::Idle),
+                (100, "dock", Status::Running),
+                (400, "dock", Status::Success),
+                (400, "mode", Status::Idle), // Monitored until the tree finished
+            ]);
+            patrol.assert_halted();
+            dock.assert_calls(1);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_switch_stops_old_branch_once() {
+        for engine in EVENT_ENGINES {
+            let mode = Handle::new(Mode::Patrol);
+            let (switch, _, patrol, _) = switch(&mode);
+            let bt = BT::new().set_engine(engine).root(BT::seq(vec![BT::switch(switch), BT::action(MockAction::new("after"))]));
+            let sim = Simulation::new().set(ms(100), &mode, Mode::Dock).run(bt).await;
+
+            let stops = sim.timeline().into_iter()
+                .filter(|(_, name, status)| *name == "patrol" && *status == Status::Idle)
+                .count();
+            assert_eq!(stops, 1);
+            assert_eq!(patrol.halts(), 1);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_switch_changes_branch_when_ticked() {
+        let mode = Handle::new(Mode::Patrol);
+        let (switch, dock, patrol, _) = switch(&mode);
+        let bt = BT::new().set_engine(Engines::Tick(ms(10))).root(BT::switch(switch));
+        let sim = Simulation::new().set(ms(105), &mode, Mode::Dock).run(bt).await;
+
+        assert_eq!(sim.result, true);
+        patrol.assert_halted();
+        dock.assert_calls(1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_switch_ignores_unchanged_value() {
+        for engine in EVENT_ENGINES {
+            let mode = Handle::new(Mode::Manual);
+            let (switch, _, _, other) = switch(&mode);
+            let bt = BT::new().set_engine(engine).root(BT::seq(vec![BT::switch(switch)]));
+            let sim = Simulation::new().set(ms(100), &mode, Mode::Manual).run(bt).await;
+
+            assert_eq!(sim.result, true);
+            other.assert_calls(1);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::time::Duration;
+    use actify::Handle;
+    use crate::{BT, testing::{MockAction, MockCondition, MockEvent}, tests::simulation::Simulation};
+
+    #[tokio::test]
+    async fn test_mock_action_scripted_results() {
+        let a = MockAction::new("a").results([false]);
+        let b = MockAction::new("b");
+        let (a_record, b_record) = (a.record(), b.record());
+
+        let bt = BT::new().root(BT::fb(vec![BT::action(a), BT::action(b)])).run().await;
+
+        assert_eq!(bt.result(), true);
+        a_record.assert_history(&[MockEvent::Started, MockEvent::Finished(false)]);
+        b_record.assert_calls(1);
+        assert_eq!(b_record.last_result(), Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_mock_action_error() {
+        let a = MockAction::new("a").error("broken");
+        let b = MockAction::new("b");
+        let (a_record, b_record) = (a.record(), b.record());
+
+        let bt = BT::new().root(BT::seq(vec![BT::action(a), BT::action(b)])).run().await;
+
+        assert_eq!(bt.result(), false);
+        a_record.assert_history(&[MockEvent::Started, MockEvent::Errored]);
+        b_record.assert_not_called();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_mock_action_halted_by_condition() {
+        let handle = Handle::new(1);
+        let cond = MockCondition::new("cond").results([true, false]);
+        let act = MockAction::new("act").delay(Duration::from_millis(500));
+        let (cond_record, act_record) = (cond.record(), act.record());
+
+        let bt = BT::new().root(BT::seq(vec![BT::condition(handle.clone(), cond), BT::action(act)]));
+        let sim = Simulation::new()
+            .set(Duration::from_millis(100), &handle, 2) // Only triggers a new evaluation
+            .run(bt)
+            .await;
+
+        assert_eq!(sim.result, false);
+        cond_record.assert_history(&[
+            MockEvent::Started,
+            MockEvent::Finished(true),
+            MockEvent::Started,
+            MockEvent::Finished(false),
+        ]);
+        act_record.assert_calls(1);
+        act_record.assert_halted();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_mock_action_scripted_delays() {
+        let a = MockAction::new("a")
+            .results([false, true])
+            .delays([Duration::from_millis(100), Duration::from_millis(300)]);
+        let record = a.record();
+
+        let bt = BT::new().root(BT::action(a));
+        let mut ticker = bt.into_ticker();
+        while ticker.tick().await.is_running() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let first = tokio::time::Instant::now();
+        ticker.tick().await; // The tree starts over
+        while ticker.tick().await.is_running() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert!(first.elapsed() >= Duration::from_millis(300));
+        record.assert_history(&[
+            MockEvent::Started,
+            MockEvent::Finished(false),
+            MockEvent::Started,
+            MockEvent::Finished(true),
+        ]);
+    }
+}
@@ -0,0 +1,154 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::{collections::HashMap, time::Duration};
+    use actify::Handle;
+    use anyhow::Result;
+    use tokio::time::sleep;
+    use crate::{Action, BT, Condition, Failure, Success, Wait, bt::Ready, execution::engine_factory::Engines, nodes::action::Executor, testing::MockAction, nodes_bin::{node::Node, node_status::Status}};
+
+    const ENGINE: Engines = Engines::Tick(Duration::from_millis(10));
+
+    // Counts how often it is executed
+    struct Counter {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Executor for Counter {
+        fn get_name(&self) -> String {
+            "counter".to_string()
+        }
+
+        async fn execute(&mut self) -> Result<bool> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(true)
+        }
+    }
+
+    async fn tick_until_done(ticker: &mut crate::Ticker) -> Status {
+        loop {
+            let status = ticker.tick().await;
+            if !status.is_running() {
+                return status;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tick_sequence_all_success() {
+        let mut map = HashMap::new();
+        map.insert("a1".to_string(), Success::new());
+        map.insert("a2".to_string(), Success::new());
+
+        let seq = Node::Sequence(vec![Node::Action("a1".into()), Node::Action("a2".into())]);
+        let bt = BT::new().test_insert_map(map).test_root(seq).set_engine(ENGINE).name("test_tree");
+
+        let result = bt.test_into_state().run().await;
+        assert_eq!(result.result(), true);
+    }
+
+    #[tokio::test]
+    async fn test_tick_fallback_all_fail() {
+        let mut map = HashMap::new();
+        map.insert("f1".to_string(), Failure::new());
+        map.insert("f2".to_string(), Failure::new());
+
+        let fb = Node::Fallback(vec![Node::Action("f1".into()), Node::Action("f2".into())]);
+        let bt = BT::new().test_insert_map(map).test_root(fb).set_engine(ENGINE).name("test_tree");
+
+        let result = bt.test_into_state().run().await;
+        assert_eq!(result.result(), false);
+    }
+
+    #[tokio::test]
+    async fn test_tick_condition_interrupt() {
+        let mut map = HashMap::new();
+        let handle = Handle::new(1);
+        map.insert("cond".to_string(), Condition::new("cond", handle.clone(), |x| x > 0));
+        map.insert("action".to_string(), Action::new(MockAction::new("1").delay(Duration::from_millis(500))));
+
+        let seq = Node::Sequence(vec![Node::Condition("cond".into()), Node::Action("action".into())]);
+        let bt = BT::new().test_insert_map(map).test_root(seq).set_engine(ENGINE).name("test_tree");
+
+        let (bt, _) = tokio::join!(
+            bt.test_into_state().run(),
+            async {
+                sleep(Duration::from_millis(200)).await;
+                handle.set(-1).await;
+            }
+        );
+
+        assert_eq!(bt.result(), false);
+    }
+
+    #[tokio::test]
+    async fn test_manual_tick_reports_running() {
+        let mut map = HashMap::new();
+        map.insert("a1".to_string(), Success::new());
+        map.insert("a2".to_string(), Success::new());
+
+        let seq = Node::Sequence(vec![Node::Action("a1".into()), Node::Action("a2".into())]);
+        let bt: BT<Ready> = BT::new().test_insert_map(map).test_root(seq).name("test_tree");
+        let mut ticker = bt.into_ticker();
+
+        assert_eq!(ticker.tick().await, Status::Running);
+        assert_eq!(tick_until_done(&mut ticker).await, Status::Success);
+    }
+
+    #[tokio::test]
+    async fn test_manual_tick_reevaluates_conditions() {
+        let mut map = HashMap::new();
+        let handle = Handle::new(0);
+        map.insert("cond".to_string(), Condition::new("cond", handle.clone(), |x| x > 0));
+        map.insert("wait".to_string(), Wait::new(Duration::from_secs(10)));
+
+        let fb = Node::Fallback(vec![Node::Condition("cond".into()), Node::Action("wait".into())]);
+        let bt: BT<Ready> = BT::new().test_insert_map(map).test_root(fb).name("test_tree");
+        let mut ticker = bt.into_ticker();
+
+        assert_eq!(ticker.tick().await, Status::Running);
+        assert_eq!(ticker.tick().await, Status::Running);
+
+        handle.set(1).await;
+        assert_eq!(ticker.tick().await, Status::Success);
+    }
+
+    #[tokio::test]
+    async fn test_manual_tick_does_not_repeat_finished_actions() {
+        let mut map = HashMap::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handle = Handle::new(1);
+        map.insert("cond".to_string(), Condition::new("cond", handle.clone(), |x| x > 0));
+        map.insert("counter".to_string(), Action::new(Counter { calls: calls.clone() }));
+        map.insert("wait".to_string(), Wait::new(Duration::from_millis(100)));
+
+        let seq = Node::Sequence(vec![
+            Node::Condition("cond".into()),
+            Node::Action("counter".into()),
+            Node::Action("wait".into()),
+        ]);
+        let bt: BT<Ready> = BT::new().test_insert_map(map).test_root(seq).name("test_tree");
+        let mut ticker = bt.into_ticker();
+
+        assert_eq!(tick_until_done(&mut ticker).await, Status::Success);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_ticker_kills_nodes() {
+        let mock = MockAction::new("long").delay(Duration::from_secs(10));
+        let record = mock.record();
+        let bt = BT::new().root(BT::action(mock)).name("test_tree");
+        let mut ticker = bt.into_ticker();
+
+        assert_eq!(ticker.tick().await, Status::Running);
+        sleep(Duration::from_millis(10)).await;
+        drop(ticker);
+
+        sleep(Duration::from_millis(10)).await;
+        record.assert_halted();
+    }
+}
@@ -8,13 +8,19 @@ mod tests {
     use tokio::time::{Duration, sleep};
     use crate::bt::Ready;
     use crate::execution::traversal::{search_next, search_start};
-    use crate::nodes::action::mocking::MockAction;
     use crate::nodes_bin::node::Node;
+    use crate::nodes_bin::node_arena::NodeIndex;
     use crate::nodes_bin::process_handle::ProcessHandle;
     use crate::nodes_bin::node_status::Status;
     use crate::{BT, Condition, Failure, Success, Wait};
     use logtest::Logger;
 
+    // Expands the node found by the search into the nodes from the root down to it
+    fn to_trace(bt: &BT<Ready>, node: Option<NodeIndex>) -> Vec<Node> {
+        node.map(|idx| bt.arena.trace(idx).into_iter().map(|i| bt.arena.to_node(i)).collect())
+            .unwrap_or_default()
+    }
+
     // * Tests for search_down()
     #[tokio::test]
     async fn test_auto_failure() {
@@ -27,7 +33,7 @@ mod tests {
         let root = Node::Action(id1);
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
-        let trace = search_start(&bt);
+        let trace = to_trace(&bt, search_start(&bt));
 
         assert_eq!(trace, vec![
             root,                  // action visited
@@ -46,7 +52,7 @@ mod tests {
         let root = Node::Action(id1.clone());
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
-        let trace = search_start(&bt);
+        let trace = to_trace(&bt, search_start(&bt));
 
         assert_eq!(trace, vec![root]);
     }
@@ -63,7 +69,7 @@ mod tests {
         let root = Node::Condition(id1.clone());
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
-        let trace = search_start(&bt);
+        let trace = to_trace(&bt, search_start(&bt));
 
         assert_eq!(trace, vec![root]);
     }
@@ -88,7 +94,7 @@ mod tests {
 
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
-        let trace = search_start(&bt);
+        let trace = to_trace(&bt, search_start(&bt));
 
         assert_eq!(trace, vec![
             root.clone(),
@@ -113,7 +119,7 @@ mod tests {
 
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
-        let trace = search_start(&bt);
+        let trace = to_trace(&bt, search_start(&bt));
 
         assert_eq!(trace, vec![
             root.clone(),
@@ -138,7 +144,7 @@ mod tests {
 
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
-        let trace = search_start(&bt);
+        let trace = to_trace(&bt, search_start(&bt));
 
         assert_eq!(trace, vec![
             root.clone(),
@@ -163,7 +169,7 @@ mod tests {
 
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
-        let trace = search_start(&bt);
+        let trace = to_trace(&bt, search_start(&bt));
 
         assert_eq!(trace, vec![
             root.clone(),
@@ -195,7 +201,7 @@ mod tests {
 
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
-        let trace = search_start(&bt);
+        let trace = to_trace(&bt, search_start(&bt));
 
         assert_eq!(trace, vec![
             root.clone(),
@@ -227,7 +233,7 @@ mod tests {
 
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
-        let trace = search_start(&bt);
+        let trace = to_trace(&bt, search_start(&bt));
 
         assert_eq!(trace, vec![
             root.clone(),
@@ -259,12 +265,12 @@ mod tests {
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
         let start = search_start(&bt);
-        assert_eq!(start, vec![
+        assert_eq!(to_trace(&bt, start), vec![
             root.clone(),
             Node::Condition("cond1".into()),
         ]);
 
-        let next = search_next(start.clone(), &Status::Success);
+        let next = to_trace(&bt, search_next(&bt.arena, start.unwrap(), &Status::Success));
 
         assert_eq!(next, vec![
             root.clone(),
@@ -290,12 +296,12 @@ mod tests {
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
         let start = search_start(&bt);
-        assert_eq!(start, vec![
+        assert_eq!(to_trace(&bt, start), vec![
             root.clone(),
             Node::Condition("cond1".into()),
         ]);
 
-        let next = search_next(start.clone(), &Status::Failure);
+        let next = to_trace(&bt, search_next(&bt.arena, start.unwrap(), &Status::Failure));
 
         assert_eq!(next, vec![]);
     }
@@ -318,12 +324,12 @@ mod tests {
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
         let start = search_start(&bt);
-        assert_eq!(start, vec![
+        assert_eq!(to_trace(&bt, start), vec![
             root.clone(),
             Node::Condition("c1".into()),
         ]);
 
-        let next = search_next(start.clone(), &Status::Failure);
+        let next = to_trace(&bt, search_next(&bt.arena, start.unwrap(), &Status::Failure));
 
         assert_eq!(next, vec![
             root.clone(),
@@ -349,12 +355,12 @@ mod tests {
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
         let start = search_start(&bt);
-        assert_eq!(start, vec![
+        assert_eq!(to_trace(&bt, start), vec![
             root.clone(),
             Node::Condition("c1".into()),
         ]);
 
-        let next = search_next(start.clone(), &Status::Success);
+        let next = to_trace(&bt, search_next(&bt.arena, start.unwrap(), &Status::Success));
 
         assert_eq!(next, vec![]);
     }
@@ -384,13 +390,13 @@ mod tests {
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
         let start = search_start(&bt);
-        assert_eq!(start, vec![
+        assert_eq!(to_trace(&bt, start), vec![
             root.clone(),
             seq.clone(),
             Node::Condition("c1".into()),
         ]);
 
-        let next = search_next(start.clone(), &Status::Failure);
+        let next = to_trace(&bt, search_next(&bt.arena, start.unwrap(), &Status::Failure));
 
         assert_eq!(next, vec![
             root.clone(),
@@ -408,9 +414,10 @@ mod tests {
         let root = Node::Action("a1".into());
         let bt = BT::new().test_insert_map(map).test_root(root.clone()).name("test_tree");
 
-        let fst_trace = search_start(&bt);
-        let snd_trace = search_next(fst_trace.clone(), &Status::Success);
-        let trd_trace = search_next(fst_trace.clone(), &Status::Failure);
+        let start = search_start(&bt);
+        let fst_trace = to_trace(&bt, start);
+        let snd_trace = to_trace(&bt, search_next(&bt.arena, start.unwrap(), &Status::Success));
+        let trd_trace = to_trace(&bt, search_next(&bt.arena, start.unwrap(), &Status::Failure));
 
         assert_eq!(fst_trace, vec![root.clone()]);
         assert_eq!(snd_trace, Vec::<Node>::new());
@@ -0,0 +1,182 @@
+#[cfg(test)]
+#[allow(unused_imports)]
+mod tests {
+    use actify::Handle;
+    use crate::{BT, Builder, Status, UtilitySelector, bt::Ready, execution::engine_factory::Engines};
+    use crate::{testing::MockAction, tests::simulation::{Simulation, ms, EVENT_ENGINES}};
+
+    fn action(name: &str, delay: u64) -> BT<Builder> {
+        BT::action(MockAction::new(name).delay(ms(delay)))
+    }
+
+    // Explore while far from the target, approach once close, with a margin of 1
+    fn utility_tree(handle: &Handle<f64>, engine: Engines) -> BT<Ready> {
+        let selector = UtilitySelector::new("utility", handle.clone())
+            .margin(1.0)
+            .child(|dist| *dist, action("explore", 500))
+            .child(|dist| 10.0 - dist, action("approach", 500));
+        BT::new().set_engine(engine).root(BT::utility(selector))
+    }
+
+    // Only the actions, leaving out the guard conditions
+    fn actions(timeline: Vec<(u128, &str, Status)>) -> Vec<(u128, &str, Status)> {
+        timeline.into_iter().filter(|(_, name, _)| !name.starts_with("utility")).collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_utility_runs_highest_score() {
+        for engine in EVENT_ENGINES {
+            let handle = Handle::new(2.0);
+            let sim = Simulation::new().run(utility_tree(&handle, engine)).await;
+
+            assert_eq!(sim.result, true);
+            assert_eq!(actions(sim.timeline()), vec![
+                (0, "approach", Status::Running),
+                (500, "approach", Status::Success),
+            ]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_utility_switches_to_later_child() {
+        for engine in EVENT_ENGINES {
+            let handle = Handle::new(8.0);
+            let sim = Simulation::new()
+                .set(ms(100), &handle, 2.0)
+                .run(utility_tree(&handle, engine))
+                .await;
+
+            assert_eq!(sim.result, true);
+            assert_eq!(actions(sim.timeline()), vec![
+                (0, "explore", Status::Running),
+                (100, "explore", Status::Idle),
+                (100, "approach", Status::Running),
+                (600, "approach", Status::Success),
+            ]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_utility_switches_to_earlier_child() {
+        for engine in EVENT_ENGINES {
+            let handle = Handle::new(2.0);
+            let sim = Simulation::new()
+                .set(ms(100), &handle, 8.0)
+                .run(utility_tree(&handle, engine))
+                .await;
+
+            assert_eq!(sim.result, true);
+            assert_eq!(actions(sim.timeline()), vec![
+                (0, "approach", Status::Running),
+                (100, "approach", Status::Idle),
+                (100, "explore", Status::Running),
+                (600, "explore", Status::Success),
+            ]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_utility_keeps_child_within_margin() {
+        for engine in EVENT_ENGINES {
+            let handle = Handle::new(4.0);
+            let sim = Simulation::new()
+                .set(ms(100), &handle, 5.4) // explore scores 0.8 higher now
+                .run(utility_tree(&handle, engine))
+                .await;
+
+            assert_eq!(actions(sim.timeline()), vec![
+                (0, "approach", Status::Running),
+                (500, "approach", Status::Success),
+            ]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_utility_returns_result_of_child() {
+        for engine in EVENT_ENGINES {
+            let selector = UtilitySelector::new("utility", Handle::new(1))
+                .child(|_| 1.0, BT::action(MockAction::new("low")))
+                .child(|_| 2.0, BT::action(MockAction::new("high").results([false])));
+            let bt = BT::new().set_engine(engine).root(BT::utility(selector));
+            assert_eq!(bt.run().await.result(), false);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_utility_fails_with_failing_first_child() {
+        for engine in EVENT_ENGINES {
+            let low = MockAction::new("low");
+            let record = low.record();
+            let selector = UtilitySelector::new("utility", Handle::new(1))
+                .child(|_| 2.0, BT::action(MockAction::new("high").results([false])))
+                .child(|_| 1.0, BT::action(low));
+            let bt = BT::new().set_engine(engine).root(BT::utility(selector));
+
+            // The lower scoring child after it does not run in its place
+            assert_eq!(bt.run().await.result(), false);
+            record.assert_not_called();
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_utility_skips_children_between() {
+        for engine in EVENT_ENGINES {
+            let handle = Handle::new(2);
+            let score = |child: i32| move |x: &i32| if *x == child { 1.0 } else { 0.0 };
+            let selector = UtilitySelector::new("utility", handle.clone())
+                .child(score(0), action("first", 500))
+                .child(score(1), action("second", 500))
+                .child(score(2), action("third", 500));
+            let bt = BT::new().set_engine(engine).root(BT::utility(selector));
+            let sim = Simulation::new().set(ms(100), &handle, 0).run(bt).await;
+
+            // Only the newly selected child starts, not the ones between it and the old one
+            assert_eq!(actions(sim.timeline()), vec![
+                (0, "third", Status::Running),
+                (100, "third", Status::Idle),
+                (100, "first", Status::Running),
+                (600, "first", Status::Success),
+            ]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_utility_forgets_selection_between_runs() {
+        let handle = Handle::new(2.0);
+        let selector = UtilitySelector::new("utility", handle.clone())
+            .margin(1.0)
+            .child(|dist| *dist, BT::action(MockAction::new("explore")))
+            .child(|dist| 10.0 - dist, BT::action(MockAction::new("approach")));
+        let bt = BT::new().root(BT::utility(selector));
+        let mut events = bt.subscribe();
+        let mut ticker = bt.into_ticker();
+
+        let mut started = || {
+            let mut names = vec![];
+            while let Ok(event) = events.try_recv() {
+                if event.status == Status::Running {
+                    names.push(event.node.name);
+                }
+            }
+            names
+        };
+        let finish = async |ticker: &mut crate::Ticker| while ticker.tick().await.is_running() {
+            tokio::task::yield_now().await;
+        };
+
+        finish(&mut ticker).await;
+        assert_eq!(started(), vec!["approach"]);
+
+        // Within the margin of approach, but a new run selects without the earlier selection
+        handle.set(5.4).await;
+        finish(&mut ticker).await;
+        assert_eq!(started(), vec!["explore"]);
+
+        handle.set(2.0).await;
+        finish(&mut ticker).await;
+        ticker.reset().await;
+        handle.set(5.4).await;
+        finish(&mut ticker).await;
+        assert_eq!(started(), vec!["approach", "explore"]);
+    }
+}